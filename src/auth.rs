@@ -1,22 +1,51 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Api, BlockInfo, Decimal, Uint128};
 
-use crate::XcosmResult;
+use crate::{ApiValidator, Expiring, Expiry, MulDiv, Rounding, Validator, XcosmResult};
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
 pub enum AuthError {
   #[error("Requestor is not authorized")]
   Unauthorized {},
+
+  #[error("Requestor does not have role {role:?}")]
+  MissingRole { role: String },
+
+  #[error("Role {role:?} has no admin role")]
+  NoRoleAdmin { role: String },
+
+  #[error("Requestor is not the owner")]
+  NotOwner {},
+
+  #[error("No ownership transfer is pending")]
+  NoPendingOwner {},
+
+  #[error("Requestor is not the pending owner")]
+  NotPendingOwner {},
+
+  #[error("Ownership transfer expired at {expiry}")]
+  TransferExpired { expiry: Expiry },
+
+  #[error("Insufficient weight: achieved {achieved}, required {required}")]
+  InsufficientWeight { achieved: u64, required: u64 },
+
+  #[error("Quorum not reached: participating weight {participating}, required {required}")]
+  QuorumNotReached { participating: u64, required: u64 },
+
+  #[error("Invalid weighted group: {reason}")]
+  InvalidWeights { reason: String },
 }
 
 /// Auth handler.
 #[cw_serde]
+#[derive(Default)]
 pub enum Authorized<T: Eq+ToString=Addr> {
   /// Single authorized address.
   One(T),
   /// Multiple authorized addresses.
   Many(Vec<T>),
   /// No authorized addresses.
+  #[default]
   None,
   /// All addresses authorized.
   Any,
@@ -34,6 +63,47 @@ impl<T: Eq+ToString> Authorized<T> {
     }
   }
 
+  /// Add a member to the group, returning whether it was added.
+  ///
+  /// [`Authorized::Any`] already contains every member and is left unchanged.
+  pub fn insert(&mut self, member: T) -> bool {
+    let (group, inserted) = match std::mem::take(self) {
+      Authorized::One(authorized) if authorized != member => {
+        (Authorized::Many(vec![authorized, member]), true)
+      }
+      Authorized::Many(mut authorized) if !authorized.contains(&member) => {
+        authorized.push(member);
+        (Authorized::Many(authorized), true)
+      }
+      Authorized::None => (Authorized::One(member), true),
+      group => (group, false),
+    };
+    *self = group;
+    inserted
+  }
+
+  /// Remove a member from the group, returning whether it was removed.
+  ///
+  /// [`Authorized::Any`] cannot exclude single members and is left unchanged.
+  pub fn remove(&mut self, member: &T) -> bool {
+    match self {
+      Authorized::One(authorized) if authorized == member => {
+        *self = Authorized::None;
+        true
+      }
+      Authorized::Many(authorized) if authorized.contains(member) => {
+        authorized.retain(|a| a != member);
+        match authorized.len() {
+          0 => *self = Authorized::None,
+          1 => *self = Authorized::One(authorized.remove(0)),
+          _ => {}
+        }
+        true
+      }
+      _ => false,
+    }
+  }
+
   /// Authorize a single requestor.
   ///
   /// Requires requestor to match authorized.
@@ -58,7 +128,7 @@ impl<T: Eq+ToString> Authorized<T> {
   /// Authorize any of the requestors.
   ///
   /// Requires at least one of `requestors` to match authorized.
-  pub fn authorize_any(&self, requestors: &Vec<T>) -> XcosmResult {
+  pub fn authorize_any(&self, requestors: &[T]) -> XcosmResult {
     match match self {
       Authorized::One(authorized) => requestors.contains(authorized),
       Authorized::Many(authorized) => requestors.iter().any(|r| authorized.contains(r)),
@@ -73,7 +143,7 @@ impl<T: Eq+ToString> Authorized<T> {
   /// Authorize all of the requestors.
   ///
   /// Requires all of `requestors` to match authorized.
-  pub fn authorize_all(&self, requestors: &Vec<T>) -> XcosmResult {
+  pub fn authorize_all(&self, requestors: &[T]) -> XcosmResult {
     match match self {
       Authorized::One(authorized) => requestors.contains(authorized),
       Authorized::Many(authorized) => requestors.iter().all(|r| authorized.contains(r)),
@@ -87,14 +157,14 @@ impl<T: Eq+ToString> Authorized<T> {
 
   /// Authorize at least `min` of the requestors.
   ///
-  /// Requires at least `min` of `requestors` to match authorized.
-  pub fn authorize_at_least(&self, requestors: &Vec<T>, min: u32) -> XcosmResult {
+  /// Requires at least `min` distinct members of authorized to be among `requestors`.
+  pub fn authorize_at_least(&self, requestors: &[T], min: u32) -> XcosmResult {
     match match self {
-      Authorized::One(authorized) => requestors.contains(authorized),
+      Authorized::One(authorized) => requestors.contains(authorized) as u32 >= min,
       Authorized::Many(authorized) => {
-        requestors.iter().filter(|r| authorized.contains(r)).count() as u32 >= min
+        authorized.iter().filter(|a| requestors.contains(a)).count() as u32 >= min
       }
-      Authorized::None => false,
+      Authorized::None => min == 0,
       Authorized::Any => true,
     } {
       true => Ok(()),
@@ -103,15 +173,249 @@ impl<T: Eq+ToString> Authorized<T> {
   }
 }
 
-impl<T: Eq+ToString> Default for Authorized<T> {
-  fn default() -> Self {
-    Authorized::None
+/// Member of a [`WeightedAuthorized`] group and its weight.
+#[cw_serde]
+pub struct Weighted<T=Addr> {
+  pub member: T,
+  pub weight: u64,
+}
+
+/// Weight required for a [`WeightedAuthorized`] group to authorize. Percentages are
+/// fractions of one and required weights are rounded up.
+#[cw_serde]
+pub enum Threshold {
+  /// Fixed approving weight.
+  Absolute { weight: u64 },
+  /// Share of the total weight of all members approving.
+  Percentage { percentage: Decimal },
+  /// Share of the total weight participating, and share of the participating weight
+  /// approving.
+  Quorum { quorum: Decimal, threshold: Decimal },
+}
+
+impl Threshold {
+  fn validate(&self, total: u64) -> XcosmResult {
+    let reason = match self {
+      Threshold::Absolute { weight } if *weight == 0 => "threshold weight must not be zero",
+      Threshold::Absolute { weight } if *weight > total => {
+        "threshold weight must not exceed the total weight"
+      }
+      Threshold::Percentage { percentage } if !is_fraction(percentage) => {
+        "threshold percentage must be above zero and at most one"
+      }
+      Threshold::Quorum { quorum, threshold }
+        if !is_fraction(quorum) || !is_fraction(threshold) =>
+      {
+        "quorum and threshold must be above zero and at most one"
+      }
+      _ => return Ok(()),
+    };
+    Err(
+      AuthError::InvalidWeights {
+        reason: reason.to_string(),
+      }
+      .into(),
+    )
+  }
+}
+
+fn is_fraction(value: &Decimal) -> bool {
+  !value.is_zero() && *value <= Decimal::one()
+}
+
+/// Share of `weight`, rounded up.
+fn weight_share(weight: u64, share: &Decimal) -> XcosmResult<u64> {
+  let required =
+    Uint128::from(weight).mul_div(share.atomics(), Decimal::one().atomics(), Rounding::Up)?;
+  Ok(required.u128() as u64)
+}
+
+/// Auth handler for groups of weighted members, authorizing once the requestors' combined
+/// weight meets the threshold.
+#[cw_serde]
+pub struct WeightedAuthorized<T: Eq+ToString=Addr> {
+  pub members: Vec<Weighted<T>>,
+  pub threshold: Threshold,
+}
+
+impl<T: Eq+ToString> WeightedAuthorized<T> {
+  /// Create a new [`WeightedAuthorized`] group.
+  ///
+  /// Requires distinct members with non-zero weights and a threshold which can be met.
+  pub fn new(members: Vec<Weighted<T>>, threshold: Threshold) -> XcosmResult<Self> {
+    let group = Self { members, threshold };
+    group.validate()?;
+    Ok(group)
+  }
+
+  /// Validate the group, returning the total weight of its members.
+  pub fn validate(&self) -> XcosmResult<u64> {
+    let mut seen = std::collections::BTreeSet::new();
+    let mut total = 0u64;
+    for member in &self.members {
+      if !seen.insert(member.member.to_string()) {
+        return Err(
+          AuthError::InvalidWeights {
+            reason: format!("duplicate member {}", member.member.to_string()),
+          }
+          .into(),
+        );
+      }
+      if member.weight == 0 {
+        return Err(
+          AuthError::InvalidWeights {
+            reason: format!("member {} has zero weight", member.member.to_string()),
+          }
+          .into(),
+        );
+      }
+      total = total
+        .checked_add(member.weight)
+        .ok_or_else(|| AuthError::InvalidWeights {
+          reason: "total weight overflows".to_string(),
+        })?;
+    }
+    if total == 0 {
+      return Err(
+        AuthError::InvalidWeights {
+          reason: "total weight must not be zero".to_string(),
+        }
+        .into(),
+      );
+    }
+    self.threshold.validate(total)?;
+    Ok(total)
+  }
+
+  /// Combined weight of the members among `requestors`, counting each member once.
+  pub fn weight_of(&self, requestors: &[T]) -> u64 {
+    self
+      .members
+      .iter()
+      .filter(|member| requestors.contains(&member.member))
+      .map(|member| member.weight)
+      .sum()
+  }
+
+  /// Authorize the requestors by weight.
+  ///
+  /// Requires the combined weight of `requestors` to meet the threshold. With
+  /// [`Threshold::Quorum`] the requestors are also the only participants.
+  pub fn authorize_weighted(&self, requestors: &[T]) -> XcosmResult {
+    self.authorize_votes(requestors, &[])
+  }
+
+  /// Authorize by weight from approving and rejecting members.
+  ///
+  /// Requires the combined weight of `approvals` to meet the threshold. With
+  /// [`Threshold::Quorum`] the weight of `approvals` and `rejections` together must also meet
+  /// the quorum, and the threshold applies to that participating weight. Members in both
+  /// lists count as approving.
+  pub fn authorize_votes(&self, approvals: &[T], rejections: &[T]) -> XcosmResult {
+    let total = self.validate()?;
+    let achieved = self.weight_of(approvals);
+    let required = match &self.threshold {
+      Threshold::Absolute { weight } => *weight,
+      Threshold::Percentage { percentage } => weight_share(total, percentage)?,
+      Threshold::Quorum { quorum, threshold } => {
+        let participating = achieved
+          + self
+            .members
+            .iter()
+            .filter(|m| rejections.contains(&m.member) && !approvals.contains(&m.member))
+            .map(|m| m.weight)
+            .sum::<u64>();
+        let required = weight_share(total, quorum)?;
+        if participating < required {
+          return Err(
+            AuthError::QuorumNotReached {
+              participating,
+              required,
+            }
+            .into(),
+          );
+        }
+        weight_share(participating, threshold)?
+      }
+    };
+    match achieved >= required {
+      true => Ok(()),
+      false => Err(AuthError::InsufficientWeight { achieved, required }.into()),
+    }
   }
 }
 
-impl<T: Eq+ToString, U: From<T>> Into<Vec<U>> for Authorized<T> {
-  fn into(self) -> Vec<U> {
+impl<T: Clone+Eq+std::fmt::Display> Authorized<Expiring<T>> {
+  /// Group of the members active at `block`.
+  pub fn active_at(&self, block: &BlockInfo) -> Authorized<T> {
     match self {
+      Authorized::One(member) if member.is_active(block) => Authorized::One(member.value.clone()),
+      Authorized::Many(members) => {
+        let active: Vec<T> = members
+          .iter()
+          .filter(|member| member.is_active(block))
+          .map(|member| member.value.clone())
+          .collect();
+        Authorized::new(active.as_slice())
+      }
+      Authorized::Any => Authorized::Any,
+      _ => Authorized::None,
+    }
+  }
+
+  /// Remove members which have expired at `block`, keeping those not yet active.
+  pub fn prune_expired(&mut self, block: &BlockInfo) {
+    *self = match std::mem::take(self) {
+      Authorized::One(member) if member.is_expired(block) => Authorized::None,
+      Authorized::Many(mut members) => {
+        members.retain(|member| !member.is_expired(block));
+        match members.len() {
+          0 => Authorized::None,
+          1 => Authorized::One(members.remove(0)),
+          _ => Authorized::Many(members),
+        }
+      }
+      group => group,
+    };
+  }
+
+  /// Authorize a single requestor at `block`.
+  ///
+  /// Requires requestor to match a member active at `block`.
+  pub fn authorize_at(&self, requestor: &T, block: &BlockInfo) -> XcosmResult {
+    self.active_at(block).authorize(requestor)
+  }
+
+  /// Authorize any of the requestors at `block`.
+  ///
+  /// Requires at least one of `requestors` to match a member active at `block`.
+  pub fn authorize_any_at(&self, requestors: &[T], block: &BlockInfo) -> XcosmResult {
+    self.active_at(block).authorize_any(requestors)
+  }
+
+  /// Authorize all of the requestors at `block`.
+  ///
+  /// Requires all of `requestors` to match members active at `block`.
+  pub fn authorize_all_at(&self, requestors: &[T], block: &BlockInfo) -> XcosmResult {
+    self.active_at(block).authorize_all(requestors)
+  }
+
+  /// Authorize at least `min` of the requestors at `block`.
+  ///
+  /// Requires at least `min` of `requestors` to match members active at `block`.
+  pub fn authorize_at_least_at(
+    &self,
+    requestors: &[T],
+    min: u32,
+    block: &BlockInfo,
+  ) -> XcosmResult {
+    self.active_at(block).authorize_at_least(requestors, min)
+  }
+}
+
+impl<T: Eq+ToString, U: From<T>> From<Authorized<T>> for Vec<U> {
+  fn from(authorized: Authorized<T>) -> Self {
+    match authorized {
       Authorized::One(authorized) => vec![authorized.into()],
       Authorized::Many(authorized) => authorized.into_iter().map(Into::into).collect(),
       Authorized::None => vec![],
@@ -119,3 +423,57 @@ impl<T: Eq+ToString, U: From<T>> Into<Vec<U>> for Authorized<T> {
     }
   }
 }
+
+impl<'a> ApiValidator<'a, Authorized> for &Authorized<String> {
+  fn api_validate(self, api: &'a dyn Api) -> XcosmResult<Authorized> {
+    Ok(match self {
+      Authorized::One(addr) => Authorized::One(api.validate(addr)?),
+      Authorized::Many(addrs) => Authorized::Many(
+        addrs
+          .iter()
+          .map(|addr| api.validate(addr))
+          .collect::<XcosmResult<_>>()?,
+      ),
+      Authorized::None => Authorized::None,
+      Authorized::Any => Authorized::Any,
+    })
+  }
+}
+
+impl<'a> ApiValidator<'a, Authorized<Expiring<Addr>>> for &Authorized<Expiring<String>> {
+  fn api_validate(self, api: &'a dyn Api) -> XcosmResult<Authorized<Expiring<Addr>>> {
+    let validate = |member: &Expiring<String>| -> XcosmResult<Expiring<Addr>> {
+      Ok(Expiring {
+        value: api.validate(&member.value)?,
+        active_from: member.active_from,
+        expiry: member.expiry,
+      })
+    };
+    Ok(match self {
+      Authorized::One(member) => Authorized::One(validate(member)?),
+      Authorized::Many(members) => {
+        Authorized::Many(members.iter().map(validate).collect::<XcosmResult<_>>()?)
+      }
+      Authorized::None => Authorized::None,
+      Authorized::Any => Authorized::Any,
+    })
+  }
+}
+
+impl<'a> ApiValidator<'a, WeightedAuthorized> for &WeightedAuthorized<String> {
+  fn api_validate(self, api: &'a dyn Api) -> XcosmResult<WeightedAuthorized> {
+    WeightedAuthorized::new(
+      self
+        .members
+        .iter()
+        .map(|member| {
+          Ok(Weighted {
+            member: api.validate(&member.member)?,
+            weight: member.weight,
+          })
+        })
+        .collect::<XcosmResult<_>>()?,
+      self.threshold.clone(),
+    )
+  }
+}
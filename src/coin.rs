@@ -3,58 +3,157 @@ use std::collections::BTreeMap;
 use std::iter::Map;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{
-  to_json_binary, Addr, AnyMsg, BankMsg, Coin, Coins, CoinsError, CosmosMsg, Uint128,
-};
+use cosmwasm_std::{Addr, BankMsg, Coin, Coins, CoinsError, CosmosMsg, Uint128};
 use derive_deref::{Deref, DerefMut};
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
 use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{TryMinusMut, XcosmError, XcosmResult};
+#[cfg(any(feature = "cosmwasm_2_0", feature = "stargate"))]
+use crate::ProtoAny;
+use crate::{MathError, TryMinusMut, TryPlusMut, XcosmError, XcosmResult};
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
 pub enum CoinError {
-  /// Coins do not meet the expected amount.
-  #[error("Insufficient coins provided: expected {expected:?}")]
-  Insufficient { expected: String },
+  /// Expected denom was not received.
+  #[error("Missing {expected}{denom}")]
+  #[diagnostic(code(xcosm::coin::missing), help("Send at least {expected}{denom}"))]
+  Missing { denom: String, expected: Uint128 },
+
+  /// Expected denom was not received, in any amount.
+  #[error("Missing {denom}")]
+  #[diagnostic(code(xcosm::coin::missing_denom), help("Send some {denom}"))]
+  MissingDenom { denom: String },
+
+  /// Denom was received in less than the expected amount.
+  #[error("Insufficient {denom}: expected {expected}, received {received}")]
+  #[diagnostic(
+    code(xcosm::coin::insufficient),
+    help("Send at least {expected}{denom}")
+  )]
+  Insufficient {
+    denom: String,
+    expected: Uint128,
+    received: Uint128,
+  },
+
+  /// Denom was received in more than the maximum amount.
+  #[error("Excess {denom}: expected at most {maximum}, received {received}")]
+  #[diagnostic(code(xcosm::coin::excess), help("Send at most {maximum}{denom}"))]
+  Excess {
+    denom: String,
+    maximum: Uint128,
+    received: Uint128,
+  },
+
+  /// Denom was received but not expected.
+  #[error("Unexpected {received}{denom}")]
+  #[diagnostic(code(xcosm::coin::unexpected_denom), help("Do not send {denom}"))]
+  UnexpectedDenom { denom: String, received: Uint128 },
+
+  /// Several coins do not match the expectation. Each failure is one of the per-denom errors.
+  #[error("Coins do not match expectation: {}", describe_failures(failures))]
+  #[diagnostic(
+    code(xcosm::coin::mismatch),
+    help("Fix each of the {} related coin errors", failures.len())
+  )]
+  Mismatch {
+    #[related]
+    failures: Vec<CoinError>,
+  },
 
   /// Expected no coins, but received some.
   #[error("Empty coins required")]
+  #[diagnostic(code(xcosm::coin::not_empty), help("Send the message without funds"))]
   NotEmpty {},
 
-  /// Action requires exact coins.
-  #[error("Exact coins required: {expected:?}")]
-  NotExact { expected: String },
-
   /// Coins lists cannot have duplicate denoms.
   #[error("Duplicate denom in coins: {denom:?}")]
+  #[diagnostic(
+    code(xcosm::coin::duplicate_denom),
+    help("Combine the {denom} amounts into a single coin")
+  )]
   DuplicateDenom { denom: String },
 
+  /// Coins lists cannot have duplicate denoms, and the duplicate was not reported.
+  #[error("Duplicate denom in coins")]
+  #[diagnostic(
+    code(xcosm::coin::duplicate_denom_unknown),
+    help("The duplicate denom is unknown; convert with `try_into_coins` to identify it")
+  )]
+  DuplicateDenomUnknown {},
+
   /// Expected coins, but received none.
   #[error("Non-empty coins required")]
+  #[diagnostic(code(xcosm::coin::empty), help("Send funds with the message"))]
   Empty {},
 
   /// Input/output match error for sending coins.
   #[error("Input coins and output coins must have equal values")]
+  #[diagnostic(
+    code(xcosm::coin::io_mismatch),
+    help("Make the outputs add up to exactly the input coins")
+  )]
   IoMismatch {},
 
   /// Coin error which _should_ never occur.
   #[error("Unexpected coin error: {msg:?}")]
+  #[diagnostic(code(xcosm::coin::unexpected))]
   Unexpected { msg: String },
 }
 
+impl CoinError {
+  /// Convert a [`CoinsError`] raised while building `Coins` from `coins`, recovering the
+  /// offending denom.
+  pub fn from_coins_error(err: CoinsError, coins: &[Coin]) -> Self {
+    match err {
+      CoinsError::DuplicateDenom => {
+        let mut seen = std::collections::BTreeSet::new();
+        match coins.iter().find(|coin| !seen.insert(coin.denom.as_str())) {
+          Some(coin) => CoinError::DuplicateDenom {
+            denom: coin.denom.clone(),
+          },
+          None => CoinError::Unexpected {
+            msg: "duplicate denom reported for coins without duplicates".to_string(),
+          },
+        }
+      }
+    }
+  }
+}
+
+/// Combine failures into the single failure, or a [`CoinError::Mismatch`] listing several.
+pub(crate) fn combine_failures(mut failures: Vec<CoinError>) -> Option<CoinError> {
+  match failures.len() {
+    0 => None,
+    1 => Some(failures.remove(0)),
+    _ => Some(CoinError::Mismatch { failures }),
+  }
+}
+
 impl From<CoinsError> for CoinError {
   /// Convert a [`CoinsError`] into a [`CoinError`].
-  fn from(e: CoinsError) -> Self {
-    match e {
-      CoinsError::DuplicateDenom => CoinError::DuplicateDenom {
-        denom: "".to_string(),
-      },
+  ///
+  /// The offending denom is not known here. Use [`try_into_coins`] or
+  /// [`CoinError::from_coins_error`] to report it.
+  fn from(err: CoinsError) -> Self {
+    match err {
+      CoinsError::DuplicateDenom => CoinError::DuplicateDenomUnknown {},
     }
   }
 }
 
+fn describe_failures(failures: &[CoinError]) -> String {
+  failures
+    .iter()
+    .map(|failure| failure.to_string())
+    .collect::<Vec<_>>()
+    .join("; ")
+}
+
 /// Sorted and dupe-checked map of coins that serializes as a list.
-#[derive(Debug, Clone, PartialEq, Eq, Deref, DerefMut)]
+///
+/// Zero amounts are equivalent to absent denoms for comparisons and set relations.
+#[derive(Debug, Clone, Eq, Deref, DerefMut)]
 pub struct CoinSet(BTreeMap<String, Uint128>);
 
 impl CoinSet {
@@ -66,8 +165,8 @@ impl CoinSet {
   /// Insert the amount into the set.
   ///
   /// Requires the denom to not already be present.
-  pub fn try_insert(&mut self, denom: &String, amount: Uint128) -> XcosmResult<&mut Uint128> {
-    match self.entry(denom.clone()) {
+  pub fn try_insert(&mut self, denom: &str, amount: Uint128) -> XcosmResult<&mut Uint128> {
+    match self.entry(denom.to_string()) {
       Entry::Occupied(entry) => Err(
         CoinError::DuplicateDenom {
           denom: entry.key().to_string(),
@@ -83,51 +182,141 @@ impl CoinSet {
     self.into_iter().collect()
   }
 
-  /// Validate expected coins and return the actual amount of the matching denom if it is
-  /// valid, or an error if the denom is not present or less than the expected amount.
-  ///
-  /// Require coins to contain the expected denom in at least the expected amount.
-  pub fn expect_coin(&self, expected: &Coin) -> XcosmResult<&Uint128> {
-    self
-      .get(&expected.denom)
-      .filter(|&amount| amount >= &expected.amount)
-      .ok_or_else(|| {
-        CoinError::Insufficient {
-          expected: expected.denom.clone(),
-        }
-        .into()
-      })
+  /// Require coins to contain the expected denom in at least the expected amount, returning
+  /// the actual amount.
+  pub fn expect_coin(&self, expected: &Coin) -> XcosmResult<Uint128> {
+    self.expect_coins([expected.clone()])?;
+    Ok(self.amount_of(&expected.denom))
   }
 
   /// Require coins to contain only the expected denom at exactly the expected amount.
   pub fn expect_coin_exact(&self, expected: &Coin) -> XcosmResult {
-    if self.expect_coin(expected)? != &expected.amount {
-      return Err(
-        CoinError::NotExact {
-          expected: expected.to_string(),
-        }
-        .into(),
-      );
-    }
-    Ok(())
+    self.expect_coins_exact([expected.clone()])
   }
 
-  /// Require coins to contain all the expected denoms in at least the expected amounts.
-  pub fn expect_coins<'a, I>(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
-    expected
-      .into_iter()
-      .map(|c| self.expect_coin(&c))
-      .collect::<Result<Vec<_>, _>>()?;
-    Ok(())
+  /// Require coins to contain all the expected denoms in at least the expected amounts. Other
+  /// denoms are allowed.
+  pub fn expect_coins(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
+    let (_, shortfall) = self.difference(&CoinSet::sum(expected)?);
+    self.expect_match(shortfall, CoinSet::default())
   }
 
   /// Require coins to contain only the expected denoms at exactly the expected amounts.
   pub fn expect_coins_exact(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
-    expected
+    let (surplus, shortfall) = self.difference(&CoinSet::sum(expected)?);
+    self.expect_match(shortfall, surplus)
+  }
+
+  /// Require coins to contain exactly the expected denoms, in any amounts.
+  pub fn expect_denoms<'a>(&self, expected: impl IntoIterator<Item=&'a str>) -> XcosmResult {
+    let expected: Vec<&str> = expected.into_iter().collect();
+    let missing = expected
+      .iter()
+      .filter(|denom| self.amount_of(denom).is_zero())
+      .map(|denom| CoinError::MissingDenom {
+        denom: denom.to_string(),
+      });
+    let unexpected = self
+      .iter()
+      .filter(|(denom, amount)| !amount.is_zero() && !expected.contains(&denom.as_str()))
+      .map(|(denom, amount)| CoinError::UnexpectedDenom {
+        denom: denom.clone(),
+        received: *amount,
+      });
+    match combine_failures(missing.chain(unexpected).collect()) {
+      Some(err) => Err(err.into()),
+      None => Ok(()),
+    }
+  }
+
+  /// Require coins to contain at least one of the expected coins, returning the first one
+  /// covered. Other denoms are allowed.
+  ///
+  /// If no option is covered, each option's shortfall is reported separately against its own
+  /// amount, in a [`CoinError::Mismatch`] when there are several options.
+  pub fn expect_any_of(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult<Coin> {
+    let expected: Vec<Coin> = expected.into_iter().collect();
+    if let Some(coin) = expected
+      .iter()
+      .find(|coin| self.amount_of(&coin.denom) >= coin.amount)
+    {
+      return Ok(coin.clone());
+    }
+    let failures = expected
       .into_iter()
-      .map(|c| self.expect_coin_exact(&c))
-      .collect::<Result<Vec<_>, _>>()?;
-    Ok(())
+      .map(|coin| {
+        let received = self.amount_of(&coin.denom);
+        match received.is_zero() {
+          true => CoinError::Missing {
+            denom: coin.denom,
+            expected: coin.amount,
+          },
+          false => CoinError::Insufficient {
+            denom: coin.denom,
+            expected: coin.amount,
+            received,
+          },
+        }
+      })
+      .collect::<Vec<_>>();
+    Err(
+      combine_failures(failures)
+        .unwrap_or(CoinError::Empty {})
+        .into(),
+    )
+  }
+
+  /// Require coins to contain no more than the expected amounts, and no other denoms.
+  pub fn expect_at_most(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
+    let (surplus, _) = self.difference(&CoinSet::sum(expected)?);
+    self.expect_match(CoinSet::default(), surplus)
+  }
+
+  /// Sum coins into a set, combining repeated denoms.
+  pub fn sum(coins: impl IntoIterator<Item=Coin>) -> XcosmResult<CoinSet> {
+    let mut res = CoinSet::default();
+    for coin in coins {
+      res.try_plus_mut(&coin)?;
+    }
+    Ok(res)
+  }
+
+  /// Fail if there is any `shortfall` below or `extra` beyond the expectation, with the
+  /// failing denom's error or a [`CoinError::Mismatch`] listing every failure.
+  fn expect_match(&self, shortfall: CoinSet, extra: CoinSet) -> XcosmResult {
+    let mut failures = vec![];
+    for (denom, amount) in shortfall.iter() {
+      let received = self.amount_of(denom);
+      failures.push(match received.is_zero() {
+        true => CoinError::Missing {
+          denom: denom.clone(),
+          expected: *amount,
+        },
+        false => CoinError::Insufficient {
+          denom: denom.clone(),
+          expected: received.checked_add(*amount).map_err(MathError::from)?,
+          received,
+        },
+      });
+    }
+    for (denom, amount) in extra.iter() {
+      let received = self.amount_of(denom);
+      failures.push(match received == *amount {
+        true => CoinError::UnexpectedDenom {
+          denom: denom.clone(),
+          received,
+        },
+        false => CoinError::Excess {
+          denom: denom.clone(),
+          maximum: received - *amount,
+          received,
+        },
+      });
+    }
+    match combine_failures(failures) {
+      Some(err) => Err(err.into()),
+      None => Ok(()),
+    }
   }
 
   /// Require coins to be empty.
@@ -149,16 +338,94 @@ impl CoinSet {
   pub fn send(&self, to: &Addr) -> XcosmResult<CosmosMsg> {
     match self.len() {
       0..1 => Ok(send_coin(
-        self.into_iter().next().ok_or_else(|| CoinError::Empty {})?,
+        self.into_iter().next().ok_or(CoinError::Empty {})?,
         to,
       )),
       _ => Ok(send_coins(self.expect_some()?, to)),
     }
   }
 
-  pub fn send_many(&self, from: &Addr, output: Vec<(&Addr, CoinSet)>) -> XcosmResult<CosmosMsg> {
+  pub fn send_many(
+    &self,
+    from: &Addr,
+    output: Vec<(&Addr, CoinSet)>,
+  ) -> XcosmResult<Vec<CosmosMsg>> {
     send_coins_many(self, from, output)
   }
+
+  /// Get the amount of `denom`, or zero if absent.
+  pub fn amount_of(&self, denom: &str) -> Uint128 {
+    self.get(denom).copied().unwrap_or_default()
+  }
+
+  /// Check whether `self` holds at least every amount in `other`.
+  pub fn is_superset_of(&self, other: &CoinSet) -> bool {
+    other
+      .iter()
+      .all(|(denom, amount)| self.amount_of(denom) >= *amount)
+  }
+
+  /// Check whether `other` holds at least every amount in `self`.
+  pub fn is_subset_of(&self, other: &CoinSet) -> bool {
+    other.is_superset_of(self)
+  }
+
+  /// Get the minimum amount of each denom held by both sets.
+  pub fn intersection(&self, other: &CoinSet) -> CoinSet {
+    CoinSet(
+      self
+        .iter()
+        .map(|(denom, amount)| (denom.clone(), (*amount).min(other.amount_of(denom))))
+        .filter(|(_, amount)| !amount.is_zero())
+        .collect(),
+    )
+  }
+
+  /// Get the maximum amount of each denom held by either set.
+  pub fn union_max(&self, other: &CoinSet) -> CoinSet {
+    let mut res = self.clone();
+    for (denom, amount) in other.iter() {
+      let max = res.entry(denom.clone()).or_default();
+      *max = (*max).max(*amount);
+    }
+    res.retain(|_, amount| !amount.is_zero());
+    res
+  }
+
+  /// Compare against `other`, returning the `surplus` held by `self` beyond `other` and the
+  /// `shortfall` of `self` below `other`, as `(surplus, shortfall)`.
+  pub fn difference(&self, other: &CoinSet) -> (CoinSet, CoinSet) {
+    let surplus = self
+      .iter()
+      .map(|(denom, amount)| (denom.clone(), amount.saturating_sub(other.amount_of(denom))))
+      .filter(|(_, amount)| !amount.is_zero())
+      .collect();
+    let shortfall = other
+      .iter()
+      .map(|(denom, amount)| (denom.clone(), amount.saturating_sub(self.amount_of(denom))))
+      .filter(|(_, amount)| !amount.is_zero())
+      .collect();
+    (CoinSet(surplus), CoinSet(shortfall))
+  }
+}
+
+impl PartialEq for CoinSet {
+  fn eq(&self, other: &Self) -> bool {
+    self.is_superset_of(other) && other.is_superset_of(self)
+  }
+}
+
+/// Coin sets are partially ordered by inclusion: a set is less than another if the other
+/// holds at least every amount it does, and incomparable if each holds more of some denom.
+impl PartialOrd for CoinSet {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    match (self.is_superset_of(other), other.is_superset_of(self)) {
+      (true, true) => Some(std::cmp::Ordering::Equal),
+      (true, false) => Some(std::cmp::Ordering::Greater),
+      (false, true) => Some(std::cmp::Ordering::Less),
+      (false, false) => None,
+    }
+  }
 }
 
 impl Default for CoinSet {
@@ -185,6 +452,16 @@ impl<'de> Deserialize<'de> for CoinSet {
   }
 }
 
+impl JsonSchema for CoinSet {
+  fn schema_name() -> String {
+    "CoinSet".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<Coin>::json_schema(gen)
+  }
+}
+
 impl std::fmt::Display for CoinSet {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     write!(
@@ -214,24 +491,24 @@ impl TryFrom<Vec<Coin>> for CoinSet {
   }
 }
 
-impl Into<Coins> for CoinSet {
+impl From<CoinSet> for Coins {
   /// Convert a [`CoinSet`] into a sorted `Coins`.
-  fn into(self) -> Coins {
-    self.into_vec().try_into().unwrap()
+  fn from(coins: CoinSet) -> Self {
+    coins.into_vec().try_into().unwrap()
   }
 }
 
-impl Into<Vec<Coin>> for CoinSet {
+impl From<CoinSet> for Vec<Coin> {
   /// Convert a [`CoinSet`] into a sorted `Vec<Coin>`.
-  fn into(self) -> Vec<Coin> {
-    self.into_iter().collect()
+  fn from(coins: CoinSet) -> Self {
+    coins.into_iter().collect()
   }
 }
 
-impl Into<Vec<Coin>> for &CoinSet {
+impl From<&CoinSet> for Vec<Coin> {
   /// Convert a [`CoinSet`] into a sorted `Vec<Coin>`.
-  fn into(self) -> Vec<Coin> {
-    self.into_iter().collect()
+  fn from(coins: &CoinSet) -> Self {
+    coins.into_iter().collect()
   }
 }
 
@@ -264,6 +541,11 @@ impl<T: IntoIterator<Item=Coin>> TryIntoCoinSet for T {
   }
 }
 
+/// Create a sorted [`Coins`] from `coins`, reporting any duplicate denom.
+pub fn try_into_coins(coins: Vec<Coin>) -> XcosmResult<Coins> {
+  Coins::try_from(coins.as_slice()).map_err(|err| CoinError::from_coins_error(err, &coins).into())
+}
+
 /// Create bank send message for single coin.
 pub fn send_coin(coin: Coin, to: &Addr) -> CosmosMsg {
   CosmosMsg::Bank(BankMsg::Send {
@@ -291,6 +573,14 @@ impl BankMsgIo {
   pub fn new(address: Addr, coins: Vec<Coin>) -> Self {
     Self { address, coins }
   }
+
+  pub fn address(&self) -> &Addr {
+    &self.address
+  }
+
+  pub fn coins(&self) -> &[Coin] {
+    &self.coins
+  }
 }
 
 /// Multi-send bank message. See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.40.0/proto/cosmos/bank/v1beta1/tx.proto#L33).
@@ -300,52 +590,63 @@ pub struct BankMsgMultiSend {
   pub outputs: Vec<BankMsgIo>,
 }
 
-/// Create bank multi-send message for multiple coins to multiple addresses. Not supported
-/// natively in `cosmwasm_std`; encodes a `/cosmos.bank.v1beta1.MsgMultiSend` as
-/// [`BankMsgMultiSend`] using [`CosmosMsg::Any`]`.
-#[cfg(feature = "cosmwasm_2_0")]
-pub fn send_coins_many(
-  coins: &CoinSet,
-  from: &Addr,
-  to: Vec<(&Addr, CoinSet)>,
-) -> XcosmResult<CosmosMsg> {
-  let mut rem: CoinSet = coins.clone();
-  let mut outputs: Vec<BankMsgIo> = Vec::with_capacity(to.len());
-  for (addr, out_coins) in to.into_iter() {
-    for coin in out_coins.into_iter() {
-      rem
-        .try_minus_mut(&coin)
-        .map_err(|_| CoinError::Insufficient {
-          expected: coin.to_string(),
-        })?;
-      outputs.push(BankMsgIo {
-        address: addr.clone(),
-        coins: vec![coin],
-      });
+impl BankMsgMultiSend {
+  /// Create a multi-send of `coins` from `from` to the outputs, skipping zero amounts.
+  ///
+  /// Requires the outputs to add up to exactly `coins`.
+  pub fn new(coins: &CoinSet, from: &Addr, to: Vec<(&Addr, CoinSet)>) -> XcosmResult<Self> {
+    let mut rem: CoinSet = coins.clone();
+    let mut outputs: Vec<BankMsgIo> = Vec::with_capacity(to.len());
+    for (addr, out_coins) in to.into_iter() {
+      let out_coins: Vec<Coin> = out_coins
+        .into_iter()
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+      for coin in out_coins.iter() {
+        rem
+          .try_minus_mut(coin)
+          .map_err(|_| CoinError::Insufficient {
+            denom: coin.denom.clone(),
+            expected: coin.amount,
+            received: rem.amount_of(&coin.denom),
+          })?;
+      }
+      if !out_coins.is_empty() {
+        outputs.push(BankMsgIo::new(addr.clone(), out_coins));
+      }
     }
+    rem.retain(|_, amount| !amount.is_zero());
+    rem.expect_none().map_err(|_| CoinError::IoMismatch {})?;
+    let inputs: Vec<BankMsgIo> = vec![BankMsgIo::new(from.clone(), coins.into())];
+    Ok(Self { inputs, outputs })
+  }
+
+  /// Convert to one bank send message per output. The inputs are ignored as bank sends are
+  /// always from the contract.
+  pub fn into_sends(self) -> Vec<CosmosMsg> {
+    self
+      .outputs
+      .into_iter()
+      .map(|output| send_coins(output.coins, &output.address))
+      .collect()
   }
-  rem.expect_none().map_err(|_| CoinError::IoMismatch {})?;
-  let inputs: Vec<BankMsgIo> = vec![BankMsgIo {
-    address: from.clone(),
-    coins: coins.into(),
-  }];
-  Ok(CosmosMsg::Any(AnyMsg {
-    type_url: "/cosmos.bank.v1beta1.MsgMultiSend".to_string(),
-    value: to_json_binary(&BankMsgMultiSend { inputs, outputs }).map_err(|err| {
-      CoinError::Unexpected {
-        msg: format!("unable to serialize BankMsgMultiSend: {}", err),
-      }
-    })?,
-  }))
 }
 
-#[cfg(not(feature = "cosmwasm_2_0"))]
+/// Create bank messages sending multiple coins from `from` to multiple addresses. Requires
+/// the outputs to add up to exactly `coins`.
+///
+/// Multi-send is not supported natively in `cosmwasm_std`, so the [`BankMsgMultiSend`] is
+/// encoded as a protobuf `/cosmos.bank.v1beta1.MsgMultiSend` using [`CosmosMsg::Any`] with
+/// the `cosmwasm_2_0` feature, or [`CosmosMsg::Stargate`] with the `stargate` feature.
+/// Without either it falls back to one [`BankMsg::Send`] per recipient.
 pub fn send_coins_many(
-  _coins: &CoinSet,
-  _from: &Addr,
+  coins: &CoinSet,
+  from: &Addr,
   to: Vec<(&Addr, CoinSet)>,
-) -> CoinResult<CosmosMsg> {
-  to.into_iter()
-    .map(|(addr, out_coins)| send_coins(out_coins.into(), addr))
-    .collect()
+) -> XcosmResult<Vec<CosmosMsg>> {
+  let msg = BankMsgMultiSend::new(coins, from, to)?;
+  #[cfg(any(feature = "cosmwasm_2_0", feature = "stargate"))]
+  return Ok(vec![msg.into_cosmos_msg()]);
+  #[cfg(not(any(feature = "cosmwasm_2_0", feature = "stargate")))]
+  return Ok(msg.into_sends());
 }
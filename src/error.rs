@@ -1,6 +1,8 @@
 use cosmwasm_std::StdError;
 
-use crate::{AuthError, CoinError, FundError, MathError, ValidateError};
+use crate::{
+  AuthError, CoinError, EscrowError, FundError, MathError, ProtoError, ValidateError, VestingError,
+};
 
 /// Type alias for `std::result::Result` with contract defaults.
 pub type XcosmResult<T=(), E=XcosmError> = std::result::Result<T, E>;
@@ -16,6 +18,10 @@ pub enum XcosmError {
   #[error(transparent)]
   Coin(#[from] CoinError),
 
+  /// Escrow error.
+  #[error(transparent)]
+  Escrow(#[from] EscrowError),
+
   /// Fund error.
   #[error(transparent)]
   Fund(#[from] FundError),
@@ -24,10 +30,18 @@ pub enum XcosmError {
   #[error(transparent)]
   Math(#[from] MathError),
 
+  /// Protobuf error.
+  #[error(transparent)]
+  Proto(#[from] ProtoError),
+
   /// Validate error.
   #[error(transparent)]
   Validate(#[from] ValidateError),
 
+  /// Vesting error.
+  #[error(transparent)]
+  Vesting(#[from] VestingError),
+
   /// CosmWasm standard error.
   #[error(transparent)]
   Std(#[from] StdError),
@@ -41,12 +55,12 @@ pub enum XcosmError {
   Parse {},
 }
 
-impl Into<StdError> for XcosmError {
+impl From<XcosmError> for StdError {
   /// Convert contract error into CosmWasm standard error.
-  fn into(self) -> StdError {
-    match self {
+  fn from(err: XcosmError) -> Self {
+    match err {
       XcosmError::Std(err) => err,
-      _ => StdError::generic_err(self.to_string()),
+      _ => StdError::generic_err(err.to_string()),
     }
   }
 }
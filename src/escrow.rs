@@ -0,0 +1,248 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, BlockInfo, CosmosMsg, MessageInfo, Storage};
+use cw_storage_plus::Map;
+
+use crate::{paginate, Authorized, CoinSet, Expiry, MessageFunds, TryPlusMut, XcosmResult};
+
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum EscrowError {
+  #[error("Escrow {id:?} does not exist")]
+  NotFound { id: String },
+
+  #[error("Escrow {id:?} already exists")]
+  AlreadyExists { id: String },
+
+  #[error("Escrow {id:?} expired at {expiry}")]
+  Expired { id: String, expiry: Expiry },
+
+  #[error("Escrow {id:?} has no arbiters and never expires, so its funds could never be paid out")]
+  Unreleasable { id: String },
+}
+
+/// Funds held in escrow for a beneficiary until released or refunded by the arbiters.
+#[cw_serde]
+pub struct Escrow {
+  /// Address which deposited the funds and receives any refund.
+  pub depositor: Addr,
+  /// Address which receives the funds on release.
+  pub beneficiary: Addr,
+  /// Funds held in escrow.
+  pub funds: CoinSet,
+  /// Expiry after which the escrow can no longer be released and anyone may refund it.
+  pub expiry: Expiry,
+  /// Arbiters which may release or refund the escrow.
+  pub arbiters: Authorized,
+}
+
+/// Escrow execute messages to embed in a contract's execute message.
+#[cw_serde]
+pub enum EscrowExecuteMsg {
+  /// Deposit the sent funds into a new escrow.
+  Deposit {
+    id: String,
+    beneficiary: String,
+    arbiters: Authorized<String>,
+    expiry: Expiry,
+  },
+  /// Add the sent funds to an existing escrow. Only the depositor may top up.
+  TopUp { id: String },
+  /// Release an escrow's funds to its beneficiary.
+  Release { id: String },
+  /// Refund an escrow's funds to its depositor.
+  Refund { id: String },
+}
+
+/// Escrow query messages to embed in a contract's query message.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum EscrowQueryMsg {
+  /// Get an escrow by id.
+  #[returns(EscrowResponse)]
+  Escrow { id: String },
+  /// List escrows sorted by id, starting after `start_after`.
+  #[returns(EscrowsResponse)]
+  Escrows {
+    start_after: Option<String>,
+    limit: Option<u32>,
+  },
+}
+
+/// Escrow and its id.
+#[cw_serde]
+pub struct EscrowResponse {
+  pub id: String,
+  pub escrow: Escrow,
+}
+
+/// Page of escrows, sorted by id.
+#[cw_serde]
+pub struct EscrowsResponse {
+  pub escrows: Vec<EscrowResponse>,
+}
+
+/// Storage-backed escrows keyed by id.
+pub struct Escrows {
+  namespace: &'static str,
+}
+
+impl Escrows {
+  /// Create new [`Escrows`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self { namespace }
+  }
+
+  fn escrows<'a>(&self) -> Map<&'a str, Escrow> {
+    Map::new(self.namespace)
+  }
+
+  /// Deposit the funds sent with `info` into a new escrow from the sender.
+  ///
+  /// Requires funds to be sent, `id` to not already exist and `expiry` to not be reached at
+  /// `block`. Without arbiters the escrow must expire, so it can at least be refunded.
+  #[allow(clippy::too_many_arguments)]
+  pub fn deposit(
+    &self,
+    storage: &mut dyn Storage,
+    info: &MessageInfo,
+    id: &str,
+    beneficiary: Addr,
+    arbiters: Authorized,
+    expiry: Expiry,
+    block: &BlockInfo,
+  ) -> XcosmResult<Escrow> {
+    if self.escrows().has(storage, id) {
+      return Err(EscrowError::AlreadyExists { id: id.to_string() }.into());
+    }
+    if expiry.is_expired(block) {
+      return Err(
+        EscrowError::Expired {
+          id: id.to_string(),
+          expiry,
+        }
+        .into(),
+      );
+    }
+    // an empty arbiter list authorizes nobody, just like no arbiters
+    let arbiters = match arbiters {
+      Authorized::Many(arbiters) => Authorized::new(arbiters.as_slice()),
+      arbiters => arbiters,
+    };
+    if arbiters == Authorized::None && expiry == Expiry::Never {
+      return Err(EscrowError::Unreleasable { id: id.to_string() }.into());
+    }
+    let funds = info.fund_set()?;
+    funds.expect_some()?;
+    let escrow = Escrow {
+      depositor: info.sender.clone(),
+      beneficiary,
+      funds,
+      expiry,
+      arbiters,
+    };
+    self.escrows().save(storage, id, &escrow)?;
+    Ok(escrow)
+  }
+
+  /// Add the funds sent with `info` to an existing escrow.
+  ///
+  /// Requires the sender to be the depositor and the escrow to not be expired.
+  pub fn top_up(
+    &self,
+    storage: &mut dyn Storage,
+    info: &MessageInfo,
+    id: &str,
+    block: &BlockInfo,
+  ) -> XcosmResult<Escrow> {
+    let mut escrow = self.load_unexpired(storage, id, block)?;
+    Authorized::One(escrow.depositor.clone()).authorize(&info.sender)?;
+    let funds = info.fund_set()?;
+    funds.expect_some()?;
+    escrow.funds.try_plus_mut(&funds)?;
+    self.escrows().save(storage, id, &escrow)?;
+    Ok(escrow)
+  }
+
+  /// Release an escrow to its beneficiary, returning the bank message paying it out.
+  ///
+  /// Requires `sender` to be an arbiter and the escrow to not be expired.
+  pub fn release(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    id: &str,
+    block: &BlockInfo,
+  ) -> XcosmResult<CosmosMsg> {
+    let escrow = self.load_unexpired(storage, id, block)?;
+    escrow.arbiters.authorize(sender)?;
+    self.escrows().remove(storage, id);
+    escrow.funds.send(&escrow.beneficiary)
+  }
+
+  /// Refund an escrow to its depositor, returning the bank message paying it out.
+  ///
+  /// Requires `sender` to be an arbiter unless the escrow is expired, after which anyone may
+  /// refund it.
+  pub fn refund(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    id: &str,
+    block: &BlockInfo,
+  ) -> XcosmResult<CosmosMsg> {
+    let escrow = self.load(storage, id)?;
+    if !escrow.expiry.is_expired(block) {
+      escrow.arbiters.authorize(sender)?;
+    }
+    self.escrows().remove(storage, id);
+    escrow.funds.send(&escrow.depositor)
+  }
+
+  /// Load an escrow by id.
+  pub fn load(&self, storage: &dyn Storage, id: &str) -> XcosmResult<Escrow> {
+    self
+      .escrows()
+      .may_load(storage, id)?
+      .ok_or_else(|| EscrowError::NotFound { id: id.to_string() }.into())
+  }
+
+  fn load_unexpired(
+    &self,
+    storage: &dyn Storage,
+    id: &str,
+    block: &BlockInfo,
+  ) -> XcosmResult<Escrow> {
+    let escrow = self.load(storage, id)?;
+    if escrow.expiry.is_expired(block) {
+      return Err(
+        EscrowError::Expired {
+          id: id.to_string(),
+          expiry: escrow.expiry,
+        }
+        .into(),
+      );
+    }
+    Ok(escrow)
+  }
+
+  /// Query an escrow by id.
+  pub fn query(&self, storage: &dyn Storage, id: &str) -> XcosmResult<EscrowResponse> {
+    Ok(EscrowResponse {
+      id: id.to_string(),
+      escrow: self.load(storage, id)?,
+    })
+  }
+
+  /// Query a page of escrows sorted by id, starting after `start_after`.
+  pub fn list(
+    &self,
+    storage: &dyn Storage,
+    start_after: Option<&str>,
+    limit: Option<u32>,
+  ) -> XcosmResult<EscrowsResponse> {
+    let escrows = paginate(storage, &self.escrows(), start_after, limit)?
+      .into_iter()
+      .map(|(id, escrow)| EscrowResponse { id, escrow })
+      .collect();
+    Ok(EscrowsResponse { escrows })
+  }
+}
@@ -0,0 +1,94 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{BlockInfo, Timestamp};
+
+/// Point at which something expires, measured by block time or block height.
+#[cw_serde]
+#[derive(Copy, Default, Eq)]
+pub enum Expiry {
+  /// Expires at the block time.
+  AtTime(Timestamp),
+  /// Expires at the block height.
+  AtHeight(u64),
+  /// Never expires.
+  #[default]
+  Never,
+}
+
+impl Expiry {
+  /// Whether the expiry has been reached at `block`.
+  pub fn is_expired(&self, block: &BlockInfo) -> bool {
+    match self {
+      Expiry::AtTime(time) => block.time >= *time,
+      Expiry::AtHeight(height) => block.height >= *height,
+      Expiry::Never => false,
+    }
+  }
+}
+
+impl std::fmt::Display for Expiry {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Expiry::AtTime(time) => write!(f, "time {}", time),
+      Expiry::AtHeight(height) => write!(f, "height {}", height),
+      Expiry::Never => write!(f, "never"),
+    }
+  }
+}
+
+/// Value which is only active between an optional activation and an expiry.
+#[cw_serde]
+#[derive(Eq)]
+pub struct Expiring<T> {
+  pub value: T,
+  /// Point at which the value becomes active. Active immediately if not set.
+  pub active_from: Option<Expiry>,
+  /// Point at which the value stops being active.
+  pub expiry: Expiry,
+}
+
+impl<T> Expiring<T> {
+  /// Create a new [`Expiring`] value, active immediately until `expiry`.
+  pub fn new(value: T, expiry: Expiry) -> Self {
+    Self {
+      value,
+      active_from: None,
+      expiry,
+    }
+  }
+
+  /// Create a new [`Expiring`] value, active from `active_from` until `expiry`.
+  pub fn delayed(value: T, active_from: Expiry, expiry: Expiry) -> Self {
+    Self {
+      value,
+      active_from: Some(active_from),
+      expiry,
+    }
+  }
+
+  /// Whether the value is active at `block`.
+  pub fn is_active(&self, block: &BlockInfo) -> bool {
+    self
+      .active_from
+      .as_ref()
+      .is_none_or(|active_from| active_from.is_expired(block))
+      && !self.expiry.is_expired(block)
+  }
+
+  /// Whether the value has expired at `block`, so it will never be active again.
+  pub fn is_expired(&self, block: &BlockInfo) -> bool {
+    self.expiry.is_expired(block)
+  }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Expiring<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.active_from {
+      Some(active_from) => write!(
+        f,
+        "{} from {} until {}",
+        self.value, active_from, self.expiry
+      ),
+      None => write!(f, "{} until {}", self.value, self.expiry),
+    }
+  }
+}
@@ -0,0 +1,147 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, CosmosMsg, Uint128};
+
+use crate::{
+  ApiValidator, Authorized, CoinError, CoinSet, Distribute, Distribution, DistributionMsg,
+  FundError, MathError, MulDiv, Rounding, Validator, XcosmResult, MAX_BPS,
+};
+
+/// Protocol fee taken from incoming funds.
+///
+/// The fee for each denom is `bps` of the amount plus any `flat` fee for the denom, clamped
+/// between any `min` and `max` for the denom. Fees are routed through `distribution` if set,
+/// otherwise they are kept by the contract.
+#[cw_serde]
+pub struct FeeConfig<A=Authorized, D=Distribution> {
+  /// Fee in basis points of each denom's amount.
+  pub bps: u32,
+  /// Flat fee added per denom.
+  pub flat: CoinSet,
+  /// Minimum fee per denom.
+  pub min: CoinSet,
+  /// Maximum fee per denom.
+  pub max: CoinSet,
+  /// Rounding of the `bps` fee. Round [`Rounding::Up`] to favour the protocol.
+  #[serde(default)]
+  pub rounding: Rounding,
+  /// Senders which pay no fee.
+  pub exempt: A,
+  /// Distribution receiving the fees.
+  pub distribution: Option<D>,
+}
+
+/// Unvalidated [`FeeConfig`].
+pub type FeeConfigMsg = FeeConfig<Authorized<String>, DistributionMsg>;
+
+impl<A: Default, D> Default for FeeConfig<A, D> {
+  fn default() -> Self {
+    Self {
+      bps: 0,
+      flat: CoinSet::default(),
+      min: CoinSet::default(),
+      max: CoinSet::default(),
+      rounding: Rounding::default(),
+      exempt: A::default(),
+      distribution: None,
+    }
+  }
+}
+
+impl FeeConfig {
+  /// Validate the fee config.
+  ///
+  /// Requires `bps` to not exceed 100% and each denom's `min` to not exceed its `max`.
+  pub fn validate(&self) -> XcosmResult {
+    if self.bps > MAX_BPS {
+      return Err(
+        FundError::FeeInvalid {
+          reason: "fee cannot exceed 100%".to_string(),
+        }
+        .into(),
+      );
+    }
+    for (denom, min) in self.min.iter() {
+      if self.max.get(denom).is_some_and(|max| min > max) {
+        return Err(
+          FundError::FeeInvalid {
+            reason: format!("minimum fee exceeds maximum fee for {}", denom),
+          }
+          .into(),
+        );
+      }
+    }
+    Ok(())
+  }
+
+  /// Calculate the fee for `amount` of `denom`, rounding the `bps` fee by `rounding`.
+  pub fn fee_amount(&self, denom: &str, amount: Uint128) -> XcosmResult<Uint128> {
+    let mut fee = amount
+      .mul_div(self.bps.into(), MAX_BPS.into(), self.rounding)?
+      .checked_add(self.flat.get(denom).copied().unwrap_or_default())
+      .map_err(MathError::from)?;
+    if let Some(min) = self.min.get(denom) {
+      fee = fee.max(*min);
+    }
+    if let Some(max) = self.max.get(denom) {
+      fee = fee.min(*max);
+    }
+    Ok(fee)
+  }
+
+  /// Take the fee from funds sent by `sender` to `contract`.
+  ///
+  /// Returns the funds net of fees, the fees taken, and the messages routing the fees through
+  /// the distribution. Exempt senders pay no fee. Requires each denom's amount to cover its
+  /// fee.
+  pub fn take_fee(
+    &self,
+    sender: &Addr,
+    funds: &CoinSet,
+    contract: &Addr,
+  ) -> XcosmResult<(CoinSet, CoinSet, Vec<CosmosMsg>)> {
+    if self.exempt.authorize(sender).is_ok() {
+      return Ok((funds.clone(), CoinSet::default(), vec![]));
+    }
+    let mut net = funds.clone();
+    let mut fee = CoinSet::default();
+    for (denom, amount) in net.iter_mut() {
+      let fee_amount = self.fee_amount(denom, *amount)?;
+      *amount = amount
+        .checked_sub(fee_amount)
+        .map_err(|_| CoinError::Insufficient {
+          denom: denom.clone(),
+          expected: fee_amount,
+          received: *amount,
+        })?;
+      if !fee_amount.is_zero() {
+        fee.insert(denom.clone(), fee_amount);
+      }
+    }
+    net.retain(|_, amount| !amount.is_zero());
+    let msgs = match &self.distribution {
+      Some(distribution) if !fee.is_empty() => distribution.distribute_coins(contract, &fee)?,
+      _ => vec![],
+    };
+    Ok((net, fee, msgs))
+  }
+}
+
+impl<'a> ApiValidator<'a, FeeConfig> for &FeeConfigMsg {
+  fn api_validate(self, api: &'a dyn Api) -> XcosmResult<FeeConfig> {
+    let config = FeeConfig {
+      bps: self.bps,
+      flat: self.flat.clone(),
+      min: self.min.clone(),
+      max: self.max.clone(),
+      rounding: self.rounding,
+      exempt: api.validate(&self.exempt)?,
+      distribution: self
+        .distribution
+        .as_ref()
+        .map(|distribution| api.validate(distribution))
+        .transpose()?,
+    };
+    config.validate()?;
+    Ok(config)
+  }
+}
@@ -1,13 +1,22 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::any::type_name;
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Coin, CosmosMsg, MessageInfo};
+use cosmwasm_std::{
+  from_json, Addr, Api, Coin, CosmosMsg, MessageInfo, StdError, Storage, Uint128, Uint256,
+};
+use cw_storage_plus::Item;
 use derive_deref::{Deref, DerefMut};
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::{
-  math::{ContainerError, TryMinusMut, TryPlusMut, ValueError},
+  coin::combine_failures,
+  math::{ContainerError, ValueError},
   validate::ApiValidator,
-  CoinError, CoinSet, IntoResult, MathError, ValidateError, Validator, XcosmError, XcosmResult,
+  CoinError, CoinSet, FeeConfig, MathError, MulDiv, Rounding, TryMinusMut, TryMulRatio, TryPlusMut,
+  ValidateError, Validator, XcosmError, XcosmResult,
 };
 
 #[derive(Debug, thiserror::Error, miette::Diagnostic)]
@@ -21,66 +30,206 @@ pub enum FundError {
   #[error("Data validation error during fund operation: {0}")]
   Validate(#[from] ValidateError),
 
-  #[error("Fund distribution claims cannot exceed 100%")]
-  DistributionOverclaimed {},
+  #[error("Fund distribution claims cannot exceed 100%: exceeded by claim for {address}")]
+  DistributionOverclaimed { address: String },
+
+  #[error("Fund distribution claims must total exactly 100%: got {bps} bps")]
+  DistributionUnderclaimed { bps: u32 },
 
   #[error("Fund distribution must contain at least one claim")]
   DistributionUnclaimed {},
 
+  #[error("Fund distribution claim for {address} must be greater than zero")]
+  ClaimZero { address: String },
+
+  #[error("Fund distribution has more than one claim for {address}")]
+  ClaimDuplicate { address: String },
+
+  #[error("Fund distribution pool {pool:?} does not exist")]
+  PoolNotFound { pool: String },
+
+  #[error("Fund distribution pool {pool:?} is nested within itself")]
+  PoolCycle { pool: String },
+
+  #[error("Fund distribution pools cannot be nested more than {max_depth} levels deep")]
+  PoolTooDeep { max_depth: u32 },
+
+  #[error("Fund distribution pool {pool:?} is not claimed from the root")]
+  PoolUnused { pool: String },
+
+  #[error("Invalid fee config: {reason}")]
+  FeeInvalid { reason: String },
+
+  #[error("No fund distribution for denom {denom:?}")]
+  DenomUnclaimed { denom: String },
+
+  #[error("No claimable funds for {address}")]
+  NothingClaimable { address: String },
+
+  #[error("Fund distribution leaves a remainder of {remainder} which is refused by policy")]
+  RemainderRefused { remainder: String },
+
   #[error("Unexpected fund error: {msg:?}")]
   Unexpected { msg: String },
 }
 
+/// Basis points representing 100% of funds.
+pub const MAX_BPS: u32 = 10000;
+
+/// Maximum number of levels in a [`DistributionTree`], including the root.
+pub const MAX_TREE_DEPTH: u32 = 8;
+
 #[cw_serde]
-#[derive(Copy)]
+#[derive(Copy, Eq)]
 pub struct Claim(u32);
 
 impl Claim {
+  pub fn new(bps: u32) -> Self {
+    Claim(bps)
+  }
+
   pub fn bps(&self) -> u32 {
     self.0
   }
 
-  pub fn claim(&self, funds: &CoinSet) -> XcosmResult<CoinSet> {
-    let mut claimed = funds.clone();
-    for (_, amount) in claimed.iter_mut() {
-      *amount = self.claim_amount(amount.u128())?.into();
-    }
-    Ok(claimed)
+  /// Claim from each denom of `funds`, rounding each amount by `rounding`.
+  pub fn claim(&self, funds: &CoinSet, rounding: Rounding) -> XcosmResult<CoinSet> {
+    Ok(funds.try_mul_ratio(Uint128::from(self.bps()), Uint128::from(MAX_BPS), rounding)?)
   }
 
-  pub fn claim_amount(&self, total: u128) -> XcosmResult<u128> {
-    total
-      .checked_mul(self.bps() as u128)
-      .ok_or(MathError::Container(ContainerError::Overflow {}))?
-      .checked_div(100000u128)
-      .ok_or(MathError::Value(ValueError::DivideByZero {}))
-      .into_result()
+  /// Claim from `total`, rounding by `rounding`.
+  pub fn claim_amount(&self, total: u128, rounding: Rounding) -> XcosmResult<u128> {
+    Ok(
+      Uint128::new(total)
+        .mul_div(self.bps().into(), MAX_BPS.into(), rounding)?
+        .u128(),
+    )
   }
 }
 
+/// Policy for allocating the remainder left after a distribution's claims are paid out.
+///
+/// Every policy is deterministic; ties between claims are broken by lowest address.
 #[cw_serde]
-#[derive(Deref, DerefMut)]
-pub struct Distribution(HashMap<Addr, Claim>);
+#[derive(Default)]
+pub enum RemainderPolicy {
+  /// Give the remainder to the largest claim.
+  #[default]
+  LargestClaim,
+  /// Give the remainder to the given address, which need not hold a claim.
+  To(Addr),
+  /// Keep the remainder with the sender.
+  Sender,
+  /// Split the funds pro rata between the claims alone, so any share left unclaimed by a
+  /// distribution below 100% is spread in proportion to the claims. The rounding remainder is
+  /// then spread one unit at a time over the claims, largest fractional part first.
+  LargestFraction,
+  /// Refuse to distribute funds which would leave a remainder.
+  Refuse,
+}
+
+/// Fund distribution splitting funds between recipients.
+pub trait Distribute {
+  /// Split funds between the recipients, rounding each share by `rounding` and allocating any
+  /// remainder according to `policy`.
+  ///
+  /// Returns the claimed funds for each recipient sorted by address. Recipients which would
+  /// receive nothing are omitted.
+  fn split_coins(
+    &self,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>>;
+
+  /// Distribute funds between the recipients, rounding each share down and allocating any
+  /// remainder according to the default [`RemainderPolicy`].
+  fn distribute_coins(&self, from: &Addr, funds: &CoinSet) -> XcosmResult<Vec<CosmosMsg>> {
+    self.distribute_coins_with(
+      from,
+      funds,
+      Rounding::default(),
+      &RemainderPolicy::default(),
+    )
+  }
+
+  /// Distribute funds between the recipients, rounding each share by `rounding` and
+  /// allocating any remainder according to `policy`.
+  ///
+  /// With [`RemainderPolicy::Sender`] only the claimed funds are sent.
+  fn distribute_coins_with(
+    &self,
+    from: &Addr,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<CosmosMsg>> {
+    send_shares(from, self.split_coins(funds, rounding, policy)?)
+  }
+}
+
+/// Single claim in a serialized [`Distribution`] or [`DistributionMsg`].
+#[cw_serde]
+pub struct DistributionEntry<T=Addr> {
+  pub address: T,
+  pub bps: Claim,
+}
+
+/// Sorted and dupe-checked map of fund claims that serializes as a list of
+/// [`DistributionEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Deref, DerefMut)]
+pub struct Distribution(BTreeMap<Addr, Claim>);
 
 impl Distribution {
-  pub fn new(claims: HashMap<Addr, Claim>) -> Self {
+  pub fn new(claims: BTreeMap<Addr, Claim>) -> Self {
     Distribution(claims)
   }
 
-  pub fn claims(&self) -> &HashMap<Addr, Claim> {
+  pub fn claims(&self) -> &BTreeMap<Addr, Claim> {
     &self.0
   }
 
+  /// Migrate a distribution stored in the legacy map layout (`{"<address>": <bps>}`) to the
+  /// canonical list layout. Distributions already in the list layout are left unchanged.
+  ///
+  /// Only a distribution stored on its own in `item` is migrated. Distributions nested in
+  /// other stored values, such as a [`FeeConfig`] or a [`DenomDistribution`], still fail to
+  /// deserialize from the legacy layout and must be rewritten by their owner.
+  pub fn migrate(storage: &mut dyn Storage, item: &Item<Distribution>) -> XcosmResult<Self> {
+    let raw = storage
+      .get(item.as_slice())
+      .ok_or_else(|| StdError::not_found(type_name::<Distribution>()))?;
+    let distribution = match from_json::<BTreeMap<String, Claim>>(&raw) {
+      Ok(legacy) => legacy
+        .into_iter()
+        .map(|(addr, claim)| (Addr::unchecked(addr), claim))
+        .collect::<BTreeMap<Addr, Claim>>()
+        .into(),
+      Err(_) => item.load(storage)?,
+    };
+    item.save(storage, &distribution)?;
+    Ok(distribution)
+  }
+
   pub fn total_bps(&self) -> XcosmResult<u32> {
-    let total = self.claims().iter().map(|(_, claim)| claim.bps()).sum();
-    if total > 10000 {
-      return Err(FundError::DistributionOverclaimed {}.into());
-    }
-    Ok(total)
+    self.claims().iter().try_fold(0u32, |total, (addr, claim)| {
+      match total
+        .checked_add(claim.bps())
+        .filter(|&total| total <= MAX_BPS)
+      {
+        Some(total) => Ok(total),
+        None => Err(
+          FundError::DistributionOverclaimed {
+            address: addr.to_string(),
+          }
+          .into(),
+        ),
+      }
+    })
   }
 
   pub fn with_remainder_to(&self, addr: Addr) -> XcosmResult<Self> {
-    let rem_claim = Claim(10000 - self.total_bps()?);
+    let rem_claim = Claim(MAX_BPS - self.total_bps()?);
     let mut claims = self.claims().clone();
     match claims.entry(addr) {
       Entry::Vacant(entry) => {
@@ -93,50 +242,79 @@ impl Distribution {
     }
     Ok(Self(claims))
   }
+}
 
-  pub fn distribute_coins(&self, from: &Addr, funds: &CoinSet) -> XcosmResult<CosmosMsg> {
-    if self.claims().len() == 0 {
-      return Err(FundError::DistributionUnclaimed {}.into());
-    }
-    let mut rem = funds.clone();
-    let mut claimed = self
+impl Distribute for Distribution {
+  /// Split funds between the claims, rounding each share by `rounding` and allocating any
+  /// remainder according to `policy`.
+  fn split_coins(
+    &self,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+    self.total_bps()?;
+    let shares = self
       .claims()
       .iter()
-      .map(|(addr, claim)| {
-        let claimed = claim.claim(funds)?;
-        rem.try_minus_mut(&claimed)?;
-        Ok((addr, claim.claim(funds)?))
-      })
-      .collect::<XcosmResult<Vec<(&Addr, CoinSet)>>>()?;
-    // give remainder to first claim
-    // TODO make this behavior configurable
-    claimed
-      .first_mut()
-      .map(|(_, coins)| coins.try_plus_mut(&rem))
-      .transpose()?
-      .ok_or_else(|| FundError::Unexpected {
-        msg: "distribution claims are not empty but no claimed funds were calculated".to_string(),
-      })?;
-    funds.send_many(from, claimed).into_result()
+      .map(|(addr, claim)| (addr, Uint128::from(claim.bps())))
+      .collect::<Vec<_>>();
+    split_shares(&shares, MAX_BPS.into(), funds, rounding, policy)
   }
 }
 
 impl Default for Distribution {
   fn default() -> Self {
-    Self::new(HashMap::new())
+    Self::new(BTreeMap::new())
+  }
+}
+
+impl Serialize for Distribution {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_entries(self.iter(), serializer, |address, claim| {
+      DistributionEntry {
+        address: address.clone(),
+        bps: *claim,
+      }
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for Distribution {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_entries(deserializer, |entry: DistributionEntry| {
+      (entry.address, entry.bps)
+    })
+    .map(Self)
+  }
+}
+
+impl JsonSchema for Distribution {
+  fn schema_name() -> String {
+    "Distribution".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<DistributionEntry>::json_schema(gen)
+  }
+}
+
+impl From<BTreeMap<Addr, Claim>> for Distribution {
+  fn from(claims: BTreeMap<Addr, Claim>) -> Self {
+    Self(claims)
   }
 }
 
 impl From<HashMap<Addr, Claim>> for Distribution {
   fn from(claims: HashMap<Addr, Claim>) -> Self {
-    Self(claims)
+    Self(claims.into_iter().collect())
   }
 }
 
-impl Into<DistributionMsg> for Distribution {
-  fn into(self) -> DistributionMsg {
+impl From<Distribution> for DistributionMsg {
+  fn from(distribution: Distribution) -> Self {
     DistributionMsg(
-      self
+      distribution
         .iter()
         .map(|(addr, claim)| (addr.to_string(), *claim))
         .collect(),
@@ -144,36 +322,920 @@ impl Into<DistributionMsg> for Distribution {
   }
 }
 
-#[cw_serde]
-#[derive(Deref, DerefMut)]
-pub struct DistributionMsg(HashMap<String, Claim>);
+/// Unvalidated [`Distribution`] that serializes as a sorted list of [`DistributionEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut)]
+pub struct DistributionMsg(BTreeMap<String, Claim>);
+
+impl Serialize for DistributionMsg {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_entries(self.iter(), serializer, |address, claim| {
+      DistributionEntry {
+        address: address.clone(),
+        bps: *claim,
+      }
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for DistributionMsg {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_entries(deserializer, |entry: DistributionEntry<String>| {
+      (entry.address, entry.bps)
+    })
+    .map(Self)
+  }
+}
+
+impl JsonSchema for DistributionMsg {
+  fn schema_name() -> String {
+    "DistributionMsg".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<DistributionEntry<String>>::json_schema(gen)
+  }
+}
 
 impl<'a> ApiValidator<'a, Distribution> for &DistributionMsg {
   fn api_validate(self, api: &dyn Api) -> XcosmResult<Distribution> {
     self
       .iter()
-      .map(|(addr_str, claim)| Ok::<_, XcosmError>((api.validate(&addr_str)?, *claim)))
-      .collect::<XcosmResult<HashMap<Addr, Claim>>>()
-      .map(Into::into)
+      .fold(DistributionBuilder::new(), |builder, (addr, claim)| {
+        builder.claim(addr, claim.bps())
+      })
+      .build(api)
   }
 }
 
+/// Builder for a validated [`Distribution`].
+///
+/// Claims are validated when the distribution is built, rejecting zero claims, duplicate
+/// addresses and totals above 100%.
+#[derive(Debug, Clone, Default)]
+pub struct DistributionBuilder {
+  claims: Vec<(String, u32)>,
+  require_full: bool,
+}
+
+impl DistributionBuilder {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Add a claim of `bps` for `addr`.
+  pub fn claim(mut self, addr: impl Into<String>, bps: u32) -> Self {
+    self.claims.push((addr.into(), bps));
+    self
+  }
+
+  /// Require the claims to total exactly 100%.
+  pub fn require_full(mut self) -> Self {
+    self.require_full = true;
+    self
+  }
+
+  /// Validate the claims and build the [`Distribution`].
+  ///
+  /// Addresses are normalized with [`Api::addr_validate`] before checking for duplicates.
+  pub fn build(self, api: &dyn Api) -> XcosmResult<Distribution> {
+    let claims = validate_claims(
+      api,
+      self.claims.iter().map(|(addr, bps)| (addr, Claim(*bps))),
+      |claim| claim.bps() == 0,
+    )?;
+    let distribution = Distribution::new(claims);
+    let total = distribution.total_bps()?;
+    if self.require_full && total != MAX_BPS {
+      return Err(FundError::DistributionUnderclaimed { bps: total }.into());
+    }
+    Ok(distribution)
+  }
+}
+
+/// Single weight in a serialized [`Weights`] or [`WeightsMsg`].
+#[cw_serde]
+pub struct WeightsEntry<T=Addr> {
+  pub address: T,
+  pub weight: Uint128,
+}
+
+/// Fund distribution in arbitrary share units, paid out pro rata against the total weight.
+///
+/// Sorted and dupe-checked map of weights that serializes as a list of [`WeightsEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut)]
+pub struct Weights(BTreeMap<Addr, Uint128>);
+
+impl Weights {
+  pub fn new(weights: BTreeMap<Addr, Uint128>) -> Self {
+    Weights(weights)
+  }
+
+  pub fn weights(&self) -> &BTreeMap<Addr, Uint128> {
+    &self.0
+  }
+
+  pub fn total_weight(&self) -> XcosmResult<Uint128> {
+    self
+      .weights()
+      .values()
+      .try_fold(Uint128::zero(), |total, weight| total.checked_add(*weight))
+      .map_err(|err| MathError::from(err).into())
+  }
+}
+
+impl Distribute for Weights {
+  /// Split funds pro rata between the weights, rounding each share by `rounding` and
+  /// allocating any remainder according to `policy`.
+  fn split_coins(
+    &self,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+    let shares = self
+      .weights()
+      .iter()
+      .map(|(addr, weight)| (addr, *weight))
+      .collect::<Vec<_>>();
+    split_shares(&shares, self.total_weight()?, funds, rounding, policy)
+  }
+}
+
+impl Serialize for Weights {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_entries(self.iter(), serializer, |address, weight| WeightsEntry {
+      address: address.clone(),
+      weight: *weight,
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for Weights {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_entries(deserializer, |entry: WeightsEntry| {
+      (entry.address, entry.weight)
+    })
+    .map(Self)
+  }
+}
+
+impl JsonSchema for Weights {
+  fn schema_name() -> String {
+    "Weights".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<WeightsEntry>::json_schema(gen)
+  }
+}
+
+impl From<BTreeMap<Addr, Uint128>> for Weights {
+  fn from(weights: BTreeMap<Addr, Uint128>) -> Self {
+    Self(weights)
+  }
+}
+
+impl From<Distribution> for Weights {
+  /// Convert a [`Distribution`] into [`Weights`] using each claim's bps as its weight.
+  ///
+  /// Any unclaimed bps are shared pro rata between the weights.
+  fn from(distribution: Distribution) -> Self {
+    distribution
+      .iter()
+      .map(|(addr, claim)| (addr.clone(), Uint128::from(claim.bps())))
+      .collect::<BTreeMap<_, _>>()
+      .into()
+  }
+}
+
+impl TryFrom<Weights> for Distribution {
+  type Error = XcosmError;
+
+  /// Convert [`Weights`] into a [`Distribution`] claiming exactly 100%.
+  ///
+  /// Bps lost to rounding are allocated by largest fractional part, ties going to the lowest
+  /// address.
+  fn try_from(weights: Weights) -> XcosmResult<Self> {
+    let shares = weights
+      .iter()
+      .map(|(addr, weight)| (addr, *weight))
+      .collect::<Vec<_>>();
+    let bps = CoinSet::new(BTreeMap::from([("bps".to_string(), MAX_BPS.into())]));
+    let claims = split_shares(
+      &shares,
+      weights.total_weight()?,
+      &bps,
+      Rounding::Down,
+      &RemainderPolicy::LargestFraction,
+    )?;
+    Ok(
+      claims
+        .into_iter()
+        .map(|(addr, claimed)| {
+          (
+            addr,
+            Claim(claimed.values().map(|amount| amount.u128() as u32).sum()),
+          )
+        })
+        .collect::<BTreeMap<_, _>>()
+        .into(),
+    )
+  }
+}
+
+/// Unvalidated [`Weights`] that serializes as a sorted list of [`WeightsEntry`].
+#[derive(Debug, Clone, PartialEq, Eq, Default, Deref, DerefMut)]
+pub struct WeightsMsg(BTreeMap<String, Uint128>);
+
+impl Serialize for WeightsMsg {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serialize_entries(self.iter(), serializer, |address, weight| WeightsEntry {
+      address: address.clone(),
+      weight: *weight,
+    })
+  }
+}
+
+impl<'de> Deserialize<'de> for WeightsMsg {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserialize_entries(deserializer, |entry: WeightsEntry<String>| {
+      (entry.address, entry.weight)
+    })
+    .map(Self)
+  }
+}
+
+impl JsonSchema for WeightsMsg {
+  fn schema_name() -> String {
+    "WeightsMsg".to_string()
+  }
+
+  fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+    Vec::<WeightsEntry<String>>::json_schema(gen)
+  }
+}
+
+impl From<Weights> for WeightsMsg {
+  fn from(weights: Weights) -> Self {
+    WeightsMsg(
+      weights
+        .iter()
+        .map(|(addr, weight)| (addr.to_string(), *weight))
+        .collect(),
+    )
+  }
+}
+
+impl<'a> ApiValidator<'a, Weights> for &WeightsMsg {
+  /// Validate the weights, requiring at least one weight, each greater than zero, and no
+  /// duplicate addresses once normalized.
+  fn api_validate(self, api: &dyn Api) -> XcosmResult<Weights> {
+    Ok(
+      validate_claims(
+        api,
+        self.iter().map(|(addr, weight)| (addr, *weight)),
+        Uint128::is_zero,
+      )?
+      .into(),
+    )
+  }
+}
+
+/// Target of a [`TreeClaim`].
+#[cw_serde]
+pub enum ClaimTarget<T=Addr> {
+  /// Pay the claim to an address.
+  Addr(T),
+  /// Split the claim again using the named pool.
+  Pool(String),
+}
+
+impl<T: Display> Display for ClaimTarget<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ClaimTarget::Addr(addr) => write!(f, "{}", addr),
+      ClaimTarget::Pool(pool) => write!(f, "pool {:?}", pool),
+    }
+  }
+}
+
+/// Claim in a [`DistributionTree`].
+#[cw_serde]
+pub struct TreeClaim<T=Addr> {
+  pub target: ClaimTarget<T>,
+  pub bps: Claim,
+}
+
+/// Multi-level fund distribution whose claims may route to nested pools.
+///
+/// The root claims are split first, then each pool claim is split again by the named pool.
+/// Pools may be nested up to [`MAX_TREE_DEPTH`] levels and may not contain themselves.
+#[cw_serde]
+pub struct DistributionTree<T=Addr> {
+  pub root: Vec<TreeClaim<T>>,
+  pub pools: BTreeMap<String, Vec<TreeClaim<T>>>,
+}
+
+/// Unvalidated [`DistributionTree`].
+pub type DistributionTreeMsg = DistributionTree<String>;
+
+impl<T> Default for DistributionTree<T> {
+  fn default() -> Self {
+    Self {
+      root: vec![],
+      pools: BTreeMap::new(),
+    }
+  }
+}
+
+impl DistributionTree {
+  /// Create a new [`DistributionTree`] with `root` claims paid directly to addresses.
+  pub fn new(root: Distribution) -> Self {
+    Self {
+      root: into_tree_claims(root),
+      pools: BTreeMap::new(),
+    }
+  }
+
+  /// Add a root claim of `bps` split by the named pool.
+  pub fn with_pool_claim(mut self, pool: impl Into<String>, bps: u32) -> Self {
+    self.root.push(TreeClaim {
+      target: ClaimTarget::Pool(pool.into()),
+      bps: Claim(bps),
+    });
+    self
+  }
+
+  /// Set the named pool to split its funds by `distribution`.
+  pub fn with_pool(mut self, pool: impl Into<String>, distribution: Distribution) -> Self {
+    self
+      .pools
+      .insert(pool.into(), into_tree_claims(distribution));
+    self
+  }
+
+  /// Total shares which the effective shares from [`DistributionTree::flatten`] are out of.
+  pub fn total_shares() -> Uint128 {
+    Uint128::from(MAX_BPS).pow(MAX_TREE_DEPTH)
+  }
+
+  /// Flatten the tree into effective per-address shares out of
+  /// [`DistributionTree::total_shares`].
+  ///
+  /// Addresses claiming through several pools have their shares combined. Requires every pool
+  /// to exist, claim at most 100% and not contain itself.
+  pub fn flatten(&self) -> XcosmResult<BTreeMap<Addr, Uint128>> {
+    let mut shares = BTreeMap::new();
+    self.flatten_claims(&self.root, Self::total_shares(), &mut vec![], &mut shares)?;
+    Ok(shares)
+  }
+
+  fn flatten_claims<'a>(
+    &'a self,
+    claims: &'a [TreeClaim],
+    total: Uint128,
+    path: &mut Vec<&'a String>,
+    shares: &mut BTreeMap<Addr, Uint128>,
+  ) -> XcosmResult {
+    if path.len() as u32 >= MAX_TREE_DEPTH {
+      return Err(
+        FundError::PoolTooDeep {
+          max_depth: MAX_TREE_DEPTH,
+        }
+        .into(),
+      );
+    }
+    claims.iter().try_fold(0u32, |bps, claim| {
+      bps
+        .checked_add(claim.bps.bps())
+        .filter(|&bps| bps <= MAX_BPS)
+        .ok_or_else(|| FundError::DistributionOverclaimed {
+          address: claim.target.to_string(),
+        })
+    })?;
+    for claim in claims {
+      // total is a multiple of MAX_BPS at every level within MAX_TREE_DEPTH
+      let share = total / Uint128::from(MAX_BPS) * Uint128::from(claim.bps.bps());
+      match &claim.target {
+        ClaimTarget::Addr(addr) => add_share(shares, addr, share)?,
+        ClaimTarget::Pool(pool) => {
+          if path.contains(&pool) {
+            return Err(FundError::PoolCycle { pool: pool.clone() }.into());
+          }
+          let (pool, pool_claims) = self
+            .pools
+            .get_key_value(pool)
+            .ok_or_else(|| FundError::PoolNotFound { pool: pool.clone() })?;
+          path.push(pool);
+          self.flatten_claims(pool_claims, share, path, shares)?;
+          path.pop();
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Validate the tree so it can be distributed through.
+  ///
+  /// Requires at least one root claim, every claim to be greater than zero, the tree to
+  /// [flatten](DistributionTree::flatten) and every pool to be claimed from the root.
+  pub fn validate(&self) -> XcosmResult {
+    if self.root.is_empty() {
+      return Err(FundError::DistributionUnclaimed {}.into());
+    }
+    let mut claims = self.root.iter().chain(self.pools.values().flatten());
+    if let Some(claim) = claims.find(|claim| claim.bps.bps() == 0) {
+      return Err(
+        FundError::ClaimZero {
+          address: claim.target.to_string(),
+        }
+        .into(),
+      );
+    }
+    self.flatten()?;
+    // flatten checked that every pool reachable from the root exists and is acyclic
+    let mut reachable = BTreeSet::new();
+    let mut pending = vec![&self.root];
+    while let Some(claims) = pending.pop() {
+      for claim in claims {
+        if let ClaimTarget::Pool(pool) = &claim.target {
+          if reachable.insert(pool) {
+            pending.push(&self.pools[pool]);
+          }
+        }
+      }
+    }
+    match self.pools.keys().find(|pool| !reachable.contains(pool)) {
+      Some(pool) => Err(FundError::PoolUnused { pool: pool.clone() }.into()),
+      None => Ok(()),
+    }
+  }
+}
+
+impl Distribute for DistributionTree {
+  /// Split funds between the effective shares, rounding each share by `rounding` and
+  /// allocating any remainder according to `policy`.
+  fn split_coins(
+    &self,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+    let shares = self.flatten()?;
+    let shares = shares
+      .iter()
+      .map(|(addr, share)| (addr, *share))
+      .collect::<Vec<_>>();
+    split_shares(&shares, Self::total_shares(), funds, rounding, policy)
+  }
+}
+
+impl From<Distribution> for DistributionTree {
+  fn from(distribution: Distribution) -> Self {
+    Self::new(distribution)
+  }
+}
+
+impl<'a> ApiValidator<'a, DistributionTree> for &DistributionTreeMsg {
+  /// Validate the addresses and structure of the tree. See [`DistributionTree::validate`].
+  fn api_validate(self, api: &dyn Api) -> XcosmResult<DistributionTree> {
+    let validate_claims = |claims: &Vec<TreeClaim<String>>| {
+      claims
+        .iter()
+        .map(|claim| {
+          Ok(TreeClaim {
+            target: match &claim.target {
+              ClaimTarget::Addr(addr) => ClaimTarget::Addr(api.validate(addr)?),
+              ClaimTarget::Pool(pool) => ClaimTarget::Pool(pool.clone()),
+            },
+            bps: claim.bps,
+          })
+        })
+        .collect::<XcosmResult<Vec<TreeClaim>>>()
+    };
+    let tree = DistributionTree {
+      root: validate_claims(&self.root)?,
+      pools: self
+        .pools
+        .iter()
+        .map(|(pool, claims)| Ok((pool.clone(), validate_claims(claims)?)))
+        .collect::<XcosmResult<_>>()?,
+    };
+    tree.validate()?;
+    Ok(tree)
+  }
+}
+
+fn into_tree_claims(distribution: Distribution) -> Vec<TreeClaim> {
+  distribution
+    .0
+    .into_iter()
+    .map(|(addr, bps)| TreeClaim {
+      target: ClaimTarget::Addr(addr),
+      bps,
+    })
+    .collect()
+}
+
+fn add_share(shares: &mut BTreeMap<Addr, Uint128>, addr: &Addr, share: Uint128) -> XcosmResult {
+  let total = shares.entry(addr.clone()).or_default();
+  *total = total.checked_add(share).map_err(MathError::from)?;
+  Ok(())
+}
+
+/// Fund distribution which splits each denom by its own [`Distribution`].
+///
+/// Denoms are matched exactly first, then by the longest matching prefix (e.g. `ibc/` or
+/// `factory/`), then fall back to the default distribution.
+#[cw_serde]
+pub struct DenomDistribution<D=Distribution> {
+  pub denoms: BTreeMap<String, D>,
+  pub prefixes: BTreeMap<String, D>,
+  pub default: Option<D>,
+}
+
+/// Unvalidated [`DenomDistribution`].
+pub type DenomDistributionMsg = DenomDistribution<DistributionMsg>;
+
+impl<D> Default for DenomDistribution<D> {
+  fn default() -> Self {
+    Self {
+      denoms: BTreeMap::new(),
+      prefixes: BTreeMap::new(),
+      default: None,
+    }
+  }
+}
+
+impl DenomDistribution {
+  /// Create a new [`DenomDistribution`] splitting unmatched denoms by `default`.
+  pub fn new(default: Option<Distribution>) -> Self {
+    Self {
+      default,
+      ..Default::default()
+    }
+  }
+
+  /// Split `denom` by `distribution`.
+  pub fn with_denom(mut self, denom: impl Into<String>, distribution: Distribution) -> Self {
+    self.denoms.insert(denom.into(), distribution);
+    self
+  }
+
+  /// Split denoms starting with `prefix` by `distribution`.
+  pub fn with_prefix(mut self, prefix: impl Into<String>, distribution: Distribution) -> Self {
+    self.prefixes.insert(prefix.into(), distribution);
+    self
+  }
+
+  /// Get the distribution for `denom`.
+  pub fn distribution_for(&self, denom: &str) -> Option<&Distribution> {
+    self
+      .denoms
+      .get(denom)
+      .or_else(|| {
+        self
+          .prefixes
+          .iter()
+          .filter(|(prefix, _)| denom.starts_with(prefix.as_str()))
+          .max_by_key(|(prefix, _)| prefix.len())
+          .map(|(_, distribution)| distribution)
+      })
+      .or(self.default.as_ref())
+  }
+}
+
+impl Distribute for DenomDistribution {
+  /// Split each denom of funds by its distribution, rounding each share by `rounding` and
+  /// allocating any remainder according to `policy`.
+  ///
+  /// Requires every denom to have a distribution.
+  fn split_coins(
+    &self,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+    let mut claimed: BTreeMap<Addr, CoinSet> = BTreeMap::new();
+    for (denom, amount) in funds.iter() {
+      let distribution = self
+        .distribution_for(denom)
+        .ok_or_else(|| FundError::DenomUnclaimed {
+          denom: denom.clone(),
+        })?;
+      let denom_funds = CoinSet::new(BTreeMap::from([(denom.clone(), *amount)]));
+      for (addr, coins) in distribution.split_coins(&denom_funds, rounding, policy)? {
+        claimed.entry(addr).or_default().try_plus_mut(&coins)?;
+      }
+    }
+    Ok(claimed.into_iter().collect())
+  }
+}
+
+impl<'a> ApiValidator<'a, DenomDistribution> for &DenomDistributionMsg {
+  fn api_validate(self, api: &dyn Api) -> XcosmResult<DenomDistribution> {
+    let validate_all = |distributions: &BTreeMap<String, DistributionMsg>| {
+      distributions
+        .iter()
+        .map(|(key, distribution)| Ok((key.clone(), api.validate(distribution)?)))
+        .collect::<XcosmResult<BTreeMap<String, Distribution>>>()
+    };
+    Ok(DenomDistribution {
+      denoms: validate_all(&self.denoms)?,
+      prefixes: validate_all(&self.prefixes)?,
+      default: self
+        .default
+        .as_ref()
+        .map(|distribution| api.validate(distribution))
+        .transpose()?,
+    })
+  }
+}
+
+/// Validate unchecked claims into a map keyed by normalized address.
+///
+/// Requires at least one claim, each greater than zero according to `is_zero`, and no
+/// duplicate addresses once normalized with [`Api::addr_validate`].
+fn validate_claims<'s, V>(
+  api: &dyn Api,
+  claims: impl IntoIterator<Item=(&'s String, V)>,
+  is_zero: impl Fn(&V) -> bool,
+) -> XcosmResult<BTreeMap<Addr, V>> {
+  let mut validated = BTreeMap::new();
+  for (addr_str, value) in claims {
+    let addr: Addr = api.validate(addr_str)?;
+    if is_zero(&value) {
+      return Err(
+        FundError::ClaimZero {
+          address: addr.to_string(),
+        }
+        .into(),
+      );
+    }
+    match validated.entry(addr) {
+      Entry::Occupied(entry) => {
+        return Err(
+          FundError::ClaimDuplicate {
+            address: entry.key().to_string(),
+          }
+          .into(),
+        )
+      }
+      Entry::Vacant(entry) => {
+        entry.insert(value);
+      }
+    }
+  }
+  if validated.is_empty() {
+    return Err(FundError::DistributionUnclaimed {}.into());
+  }
+  Ok(validated)
+}
+
+/// Split funds pro rata between `shares` of `denominator`, rounding each share by `rounding`
+/// and allocating the remainder according to `policy`. With
+/// [`RemainderPolicy::LargestFraction`] the shares are taken of their own total instead of
+/// `denominator`. Amounts are calculated with [`Uint256`] intermediates so they cannot
+/// overflow.
+///
+/// Rounding up can claim more than the funds, in which case the excess is taken back one unit
+/// at a time from the shares that were rounded up furthest, ties going to the highest
+/// address.
+fn split_shares(
+  shares: &[(&Addr, Uint128)],
+  denominator: Uint128,
+  funds: &CoinSet,
+  rounding: Rounding,
+  policy: &RemainderPolicy,
+) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+  if shares.is_empty() {
+    return Err(FundError::DistributionUnclaimed {}.into());
+  }
+  let denominator = match policy {
+    RemainderPolicy::LargestFraction => shares
+      .iter()
+      .try_fold(Uint128::zero(), |total, (_, weight)| {
+        total.checked_add(*weight)
+      })
+      .map_err(MathError::from)?,
+    _ => denominator,
+  };
+  if denominator.is_zero() {
+    return Err(MathError::Value(ValueError::DivideByZero {}).into());
+  }
+  let mut claimed = shares
+    .iter()
+    .map(|(addr, _)| ((*addr).clone(), CoinSet::default()))
+    .collect::<Vec<(Addr, CoinSet)>>();
+  let mut rem = CoinSet::default();
+  let denominator = Uint256::from(denominator);
+  for (denom, amount) in funds.iter() {
+    let mut total = Uint256::zero();
+    let mut fractions = Vec::with_capacity(shares.len());
+    let mut raised = Vec::new();
+    for (i, (_, weight)) in shares.iter().enumerate() {
+      let product = amount.full_mul(*weight);
+      let share = rounding.div(product, denominator)?;
+      let fraction = product % denominator;
+      total += share;
+      claimed[i].1.insert(
+        denom.clone(),
+        share
+          .try_into()
+          .map_err(|_| MathError::Container(ContainerError::Overflow {}))?,
+      );
+      match share > product / denominator {
+        // a share rounded up has already received its fraction
+        true => {
+          raised.push((i, fraction));
+          fractions.push((i, Uint256::zero()));
+        }
+        false => fractions.push((i, fraction)),
+      }
+    }
+    let amount = Uint256::from(*amount);
+    if total > amount {
+      let excess = Uint128::try_from(total - amount)
+        .ok()
+        .filter(|excess| excess.u128() <= raised.len() as u128)
+        .ok_or(MathError::Container(ContainerError::Underflow {}))?;
+      // the smallest fraction was rounded up furthest, the highest address loses a tie
+      raised.sort_by(|(a, x), (b, y)| x.cmp(y).then(b.cmp(a)));
+      for (i, _) in raised.into_iter().take(excess.u128() as usize) {
+        claimed[i].1.try_minus_mut(&Coin::new(1u128, denom))?;
+      }
+      continue;
+    }
+    let left = Uint128::try_from(amount - total)
+      .map_err(|_| MathError::Container(ContainerError::Overflow {}))?;
+    if left.is_zero() {
+      continue;
+    }
+    match policy {
+      RemainderPolicy::LargestClaim => {
+        // shares are sorted by address so ties go to the lowest address
+        let idx = (0..shares.len()).fold(0, |max, i| match shares[i].1 > shares[max].1 {
+          true => i,
+          false => max,
+        });
+        claimed[idx].1.try_plus_mut(&Coin::new(left, denom))?;
+      }
+      RemainderPolicy::LargestFraction => {
+        // stable sort keeps address order between equal fractions
+        fractions.sort_by(|(_, a), (_, b)| b.cmp(a));
+        let count = Uint128::from(fractions.len() as u128);
+        let (each, extra) = (left / count, left % count);
+        for (pos, (i, _)) in fractions.into_iter().enumerate() {
+          let amount = match Uint128::from(pos as u128) < extra {
+            true => each + Uint128::one(),
+            false => each,
+          };
+          claimed[i].1.try_plus_mut(&Coin::new(amount, denom))?;
+        }
+      }
+      RemainderPolicy::To(_) | RemainderPolicy::Sender | RemainderPolicy::Refuse => {
+        rem.insert(denom.clone(), left);
+      }
+    }
+  }
+  if !rem.is_empty() {
+    match policy {
+      RemainderPolicy::To(addr) => match claimed.iter_mut().find(|(a, _)| a == addr) {
+        Some((_, coins)) => coins.try_plus_mut(&rem)?,
+        None => {
+          claimed.push((addr.clone(), rem));
+          claimed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+      },
+      RemainderPolicy::Refuse => {
+        return Err(
+          FundError::RemainderRefused {
+            remainder: rem.to_string(),
+          }
+          .into(),
+        )
+      }
+      _ => {}
+    }
+  }
+  for (_, coins) in claimed.iter_mut() {
+    coins.retain(|_, amount| !amount.is_zero());
+  }
+  claimed.retain(|(_, coins)| !coins.is_empty());
+  Ok(claimed)
+}
+
+/// Send claimed funds from `from`. See [`send_coins_many`](crate::send_coins_many).
+fn send_shares(from: &Addr, claimed: Vec<(Addr, CoinSet)>) -> XcosmResult<Vec<CosmosMsg>> {
+  let mut sent = CoinSet::default();
+  for (_, coins) in claimed.iter() {
+    sent.try_plus_mut(coins)?;
+  }
+  sent.send_many(
+    from,
+    claimed
+      .iter()
+      .map(|(addr, coins)| (addr, coins.clone()))
+      .collect(),
+  )
+}
+
+fn serialize_entries<'a, K: 'a, V: 'a, E: Serialize, S: Serializer>(
+  entries: impl ExactSizeIterator<Item=(&'a K, &'a V)>,
+  serializer: S,
+  into_entry: impl Fn(&K, &V) -> E,
+) -> Result<S::Ok, S::Error> {
+  let mut seq = serializer.serialize_seq(Some(entries.len()))?;
+  for (key, value) in entries {
+    seq.serialize_element(&into_entry(key, value))?;
+  }
+  seq.end()
+}
+
+fn deserialize_entries<'de, K: Ord+Display, V, E: Deserialize<'de>, D: Deserializer<'de>>(
+  deserializer: D,
+  from_entry: impl Fn(E) -> (K, V),
+) -> Result<BTreeMap<K, V>, D::Error> {
+  let raw: Vec<E> = Deserialize::deserialize(deserializer)?;
+  let mut entries = BTreeMap::new();
+  for (key, value) in raw.into_iter().map(from_entry) {
+    match entries.entry(key) {
+      Entry::Occupied(entry) => {
+        return Err(serde::de::Error::custom(format!(
+          "duplicate address: {}",
+          entry.key()
+        )))
+      }
+      Entry::Vacant(entry) => {
+        entry.insert(value);
+      }
+    }
+  }
+  Ok(entries)
+}
+
+/// Handling of denoms sent with a message but not required by
+/// [`MessageFunds::take_funds_with`].
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum UnexpectedDenoms {
+  /// Refund unexpected denoms along with any surplus of the required denoms.
+  #[default]
+  Refund,
+  /// Reject the message if any unexpected denom is sent.
+  Reject,
+}
+
 pub trait MessageFunds {
   fn expect_funds(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult;
   fn expect_funds_exact(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult;
+  fn expect_funds_denoms<'a>(&self, expected: impl IntoIterator<Item=&'a str>) -> XcosmResult;
+  fn expect_funds_any_of(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult<Coin>;
+  fn expect_funds_at_most(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult;
   fn expect_no_funds(&self) -> XcosmResult;
   fn fund_set(&self) -> XcosmResult<CoinSet>;
+  fn take_fee(
+    &self,
+    config: &FeeConfig,
+    contract: &Addr,
+  ) -> XcosmResult<(CoinSet, CoinSet, Vec<CosmosMsg>)>;
+  fn take_funds(
+    &self,
+    required: impl IntoIterator<Item=Coin>,
+  ) -> XcosmResult<(CoinSet, Option<CosmosMsg>)>;
+  fn take_funds_with(
+    &self,
+    required: impl IntoIterator<Item=Coin>,
+    unexpected: UnexpectedDenoms,
+  ) -> XcosmResult<(CoinSet, Option<CosmosMsg>)>;
 }
 
 impl MessageFunds for MessageInfo {
-  fn expect_funds<'a>(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
-    self.fund_set()?.expect_coins_exact(expected)
+  /// Require the funds to contain at least the expected coins. See [`CoinSet::expect_coins`].
+  fn expect_funds(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
+    self.fund_set()?.expect_coins(expected)
   }
 
-  fn expect_funds_exact<'a>(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
+  /// Require the funds to be exactly the expected coins. See [`CoinSet::expect_coins_exact`].
+  fn expect_funds_exact(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
     self.fund_set()?.expect_coins_exact(expected)
   }
 
+  /// Require the funds to be exactly the expected denoms. See [`CoinSet::expect_denoms`].
+  fn expect_funds_denoms<'a>(&self, expected: impl IntoIterator<Item=&'a str>) -> XcosmResult {
+    self.fund_set()?.expect_denoms(expected)
+  }
+
+  /// Require the funds to contain one of the expected coins. See [`CoinSet::expect_any_of`].
+  fn expect_funds_any_of(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult<Coin> {
+    self.fund_set()?.expect_any_of(expected)
+  }
+
+  /// Require the funds to not exceed the expected coins. See [`CoinSet::expect_at_most`].
+  fn expect_funds_at_most(&self, expected: impl IntoIterator<Item=Coin>) -> XcosmResult {
+    self.fund_set()?.expect_at_most(expected)
+  }
+
   fn expect_no_funds(&self) -> XcosmResult {
     self.fund_set()?.expect_none()
   }
@@ -181,4 +1243,57 @@ impl MessageFunds for MessageInfo {
   fn fund_set(&self) -> XcosmResult<CoinSet> {
     self.funds.clone().try_into()
   }
+
+  /// Take the protocol fee from the funds, returning the net funds, the fees taken and the
+  /// messages routing the fees.
+  fn take_fee(
+    &self,
+    config: &FeeConfig,
+    contract: &Addr,
+  ) -> XcosmResult<(CoinSet, CoinSet, Vec<CosmosMsg>)> {
+    config.take_fee(&self.sender, &self.fund_set()?, contract)
+  }
+
+  /// Take the required funds, refunding any surplus to the sender. See
+  /// [`MessageFunds::take_funds_with`].
+  fn take_funds(
+    &self,
+    required: impl IntoIterator<Item=Coin>,
+  ) -> XcosmResult<(CoinSet, Option<CosmosMsg>)> {
+    self.take_funds_with(required, UnexpectedDenoms::default())
+  }
+
+  /// Take the required funds, returning the funds taken and the message refunding any surplus
+  /// amounts to the sender. Denoms which are not required are refunded or rejected according
+  /// to `unexpected`.
+  ///
+  /// Requires the funds to cover every required amount.
+  fn take_funds_with(
+    &self,
+    required: impl IntoIterator<Item=Coin>,
+    unexpected: UnexpectedDenoms,
+  ) -> XcosmResult<(CoinSet, Option<CosmosMsg>)> {
+    let taken = CoinSet::sum(required)?;
+    let funds = self.fund_set()?;
+    funds.expect_coins(&taken)?;
+    let (surplus, _) = funds.difference(&taken);
+    if unexpected == UnexpectedDenoms::Reject {
+      let failures = funds
+        .iter()
+        .filter(|(denom, _)| !taken.contains_key(*denom))
+        .map(|(denom, received)| CoinError::UnexpectedDenom {
+          denom: denom.clone(),
+          received: *received,
+        })
+        .collect();
+      if let Some(err) = combine_failures(failures) {
+        return Err(err.into());
+      }
+    }
+    let refund = match surplus.is_empty() {
+      true => None,
+      false => Some(surplus.send(&self.sender)?),
+    };
+    Ok((taken, refund))
+  }
 }
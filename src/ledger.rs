@@ -0,0 +1,119 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, CosmosMsg, Storage};
+use cw_storage_plus::Map;
+
+use crate::{
+  paginate, CoinSet, Distribute, FundError, RemainderPolicy, Rounding, TryPlusMut, XcosmResult,
+};
+
+/// Pending balance of a single recipient.
+#[cw_serde]
+pub struct PendingResponse {
+  pub address: Addr,
+  pub pending: CoinSet,
+}
+
+/// Page of pending balances, sorted by address.
+#[cw_serde]
+pub struct AllPendingResponse {
+  pub balances: Vec<PendingResponse>,
+}
+
+/// Storage-backed ledger of claimable balances for pull-based distributions.
+///
+/// Funds are credited to recipients as they arrive and withdrawn later by each recipient, so
+/// a recipient which cannot receive funds does not block the others.
+pub struct ClaimLedger {
+  namespace: &'static str,
+}
+
+impl ClaimLedger {
+  /// Create a new [`ClaimLedger`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self { namespace }
+  }
+
+  fn balances<'a>(&self) -> Map<&'a Addr, CoinSet> {
+    Map::new(self.namespace)
+  }
+
+  /// Credit coins to the balance of `addr`.
+  pub fn credit(&self, storage: &mut dyn Storage, addr: &Addr, coins: &CoinSet) -> XcosmResult {
+    let mut balance = self.pending(storage, addr)?;
+    balance.try_plus_mut(coins)?;
+    if !balance.is_empty() {
+      self.balances().save(storage, addr, &balance)?;
+    }
+    Ok(())
+  }
+
+  /// Credit each recipient's claimed funds, as returned by `split_coins`.
+  pub fn credit_all(&self, storage: &mut dyn Storage, claimed: &[(Addr, CoinSet)]) -> XcosmResult {
+    for (addr, coins) in claimed {
+      self.credit(storage, addr, coins)?;
+    }
+    Ok(())
+  }
+
+  /// Credit each claim's share of incoming funds, rounding each share by `rounding` and
+  /// allocating any remainder according to `policy`.
+  ///
+  /// Returns the credited funds for each recipient sorted by address. With
+  /// [`RemainderPolicy::Sender`] the remainder is left uncredited.
+  pub fn credit_distribution(
+    &self,
+    storage: &mut dyn Storage,
+    distribution: &impl Distribute,
+    funds: &CoinSet,
+    rounding: Rounding,
+    policy: &RemainderPolicy,
+  ) -> XcosmResult<Vec<(Addr, CoinSet)>> {
+    let claimed = distribution.split_coins(funds, rounding, policy)?;
+    self.credit_all(storage, &claimed)?;
+    Ok(claimed)
+  }
+
+  /// Withdraw the full pending balance of `addr`, returning the bank message paying it out.
+  ///
+  /// Requires `addr` to have a non-empty pending balance.
+  pub fn withdraw(&self, storage: &mut dyn Storage, addr: &Addr) -> XcosmResult<CosmosMsg> {
+    let balance = self.pending(storage, addr)?;
+    if balance.is_empty() {
+      return Err(
+        FundError::NothingClaimable {
+          address: addr.to_string(),
+        }
+        .into(),
+      );
+    }
+    self.balances().remove(storage, addr);
+    balance.send(addr)
+  }
+
+  /// Get the pending balance of `addr`, empty if nothing has been credited.
+  pub fn pending(&self, storage: &dyn Storage, addr: &Addr) -> XcosmResult<CoinSet> {
+    Ok(self.balances().may_load(storage, addr)?.unwrap_or_default())
+  }
+
+  /// Query the pending balance of `addr`.
+  pub fn query_pending(&self, storage: &dyn Storage, addr: &Addr) -> XcosmResult<PendingResponse> {
+    Ok(PendingResponse {
+      address: addr.clone(),
+      pending: self.pending(storage, addr)?,
+    })
+  }
+
+  /// Query a page of pending balances sorted by address, starting after `start_after`.
+  pub fn all_pending(
+    &self,
+    storage: &dyn Storage,
+    start_after: Option<&Addr>,
+    limit: Option<u32>,
+  ) -> XcosmResult<AllPendingResponse> {
+    let balances = paginate(storage, &self.balances(), start_after, limit)?
+      .into_iter()
+      .map(|(address, pending)| PendingResponse { address, pending })
+      .collect();
+    Ok(AllPendingResponse { balances })
+  }
+}
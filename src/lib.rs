@@ -2,13 +2,35 @@
 pub mod auth;
 pub mod coin;
 pub mod error;
+pub mod escrow;
+pub mod expiry;
+pub mod fee;
 pub mod fund;
+pub mod ledger;
 pub mod math;
+pub mod ownership;
+pub mod page;
+pub mod proto;
+pub mod role;
+pub mod sdk;
+pub mod stored_auth;
 pub mod validate;
+pub mod vesting;
 
 pub use auth::*;
 pub use coin::*;
 pub use error::*;
+pub use escrow::*;
+pub use expiry::*;
+pub use fee::*;
 pub use fund::*;
+pub use ledger::*;
 pub use math::*;
+pub use ownership::*;
+pub use page::*;
+pub use proto::*;
+pub use role::*;
+pub use sdk::*;
+pub use stored_auth::*;
 pub use validate::*;
+pub use vesting::*;
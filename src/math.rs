@@ -1,4 +1,7 @@
-use cosmwasm_std::Coin;
+use std::cmp::Ordering;
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, Decimal, Uint128, Uint256, Uint512};
 
 use crate::CoinSet;
 
@@ -34,6 +37,112 @@ impl From<cosmwasm_std::OverflowError> for MathError {
   }
 }
 
+impl From<cosmwasm_std::CheckedMultiplyRatioError> for MathError {
+  fn from(err: cosmwasm_std::CheckedMultiplyRatioError) -> Self {
+    match err {
+      cosmwasm_std::CheckedMultiplyRatioError::DivideByZero => ValueError::DivideByZero {}.into(),
+      cosmwasm_std::CheckedMultiplyRatioError::Overflow => ContainerError::Overflow {}.into(),
+    }
+  }
+}
+
+/// Rounding mode for integer division.
+///
+/// All amounts are unsigned, so [`Rounding::TowardZero`] is equivalent to [`Rounding::Down`].
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum Rounding {
+  /// Round toward negative infinity (floor).
+  #[default]
+  Down,
+  /// Round toward positive infinity (ceiling).
+  Up,
+  /// Round to the nearest integer, with ties away from zero.
+  HalfUp,
+  /// Round to the nearest integer, with ties to the even integer (banker's rounding).
+  HalfEven,
+  /// Round toward zero (truncate).
+  TowardZero,
+}
+
+impl Rounding {
+  /// Divide `numerator` by `denominator`, rounding the quotient.
+  pub fn div(&self, numerator: Uint256, denominator: Uint256) -> MathResult<Uint256> {
+    self
+      .div_wide(numerator.into(), denominator.into())?
+      .try_into()
+      .map_err(|_| ContainerError::Overflow {}.into())
+  }
+
+  fn div_wide(&self, numerator: Uint512, denominator: Uint512) -> MathResult<Uint512> {
+    if denominator.is_zero() {
+      return Err(ValueError::DivideByZero {}.into());
+    }
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    let half = remainder.cmp(&(denominator - remainder));
+    let round_up = match self {
+      _ if remainder.is_zero() => false,
+      Rounding::Down | Rounding::TowardZero => false,
+      Rounding::Up => true,
+      Rounding::HalfUp => half != Ordering::Less,
+      Rounding::HalfEven => match half {
+        Ordering::Less => false,
+        Ordering::Greater => true,
+        Ordering::Equal => quotient % Uint512::from(2u8) == Uint512::one(),
+      },
+    };
+    match round_up {
+      true => Ok(quotient.checked_add(Uint512::one())?),
+      false => Ok(quotient),
+    }
+  }
+}
+
+/// Checked `self * numerator / denominator` with explicit rounding. The intermediate product
+/// is calculated at double width, so only a result exceeding `Self` overflows.
+pub trait MulDiv: Sized {
+  fn mul_div(self, numerator: Self, denominator: Self, rounding: Rounding) -> MathResult<Self>;
+}
+
+impl MulDiv for Uint128 {
+  fn mul_div(self, numerator: Self, denominator: Self, rounding: Rounding) -> MathResult<Self> {
+    rounding
+      .div_wide(self.full_mul(numerator).into(), denominator.into())?
+      .try_into()
+      .map_err(|_| ContainerError::Overflow {}.into())
+  }
+}
+
+impl MulDiv for Uint256 {
+  fn mul_div(self, numerator: Self, denominator: Self, rounding: Rounding) -> MathResult<Self> {
+    rounding
+      .div_wide(self.full_mul(numerator), denominator.into())?
+      .try_into()
+      .map_err(|_| ContainerError::Overflow {}.into())
+  }
+}
+
+impl MulDiv for Decimal {
+  fn mul_div(self, numerator: Self, denominator: Self, rounding: Rounding) -> MathResult<Self> {
+    // the fixed point scale cancels out: (a / s) * (b / s) / (c / s) = (a * b / c) / s
+    Ok(Decimal::new(self.atomics().mul_div(
+      numerator.atomics(),
+      denominator.atomics(),
+      rounding,
+    )?))
+  }
+}
+
+/// Checked `a * b / c` with explicit rounding. See [`MulDiv`].
+pub fn mul_div<T: MulDiv>(a: T, b: T, c: T, rounding: Rounding) -> MathResult<T> {
+  a.mul_div(b, c, rounding)
+}
+
+/// Checked addition. For [`CoinSet`] this is a union: denoms missing from `self` are
+/// inserted.
+///
+/// [`CoinSet`] results never hold zero amounts.
 pub trait TryPlus<T> {
   type Output;
   type Error;
@@ -47,17 +156,8 @@ impl TryPlus<&Coin> for CoinSet {
 
   fn try_plus(&self, other: &Coin) -> MathResult<Self> {
     let mut res = self.clone();
-    let mut is_err = false;
-    res
-      .entry(other.denom.clone())
-      .and_modify(|amount| match (*amount).checked_add(other.amount) {
-        Ok(amt) => *amount = amt,
-        Err(_) => is_err = true,
-      });
-    match is_err {
-      true => Err(ContainerError::Overflow {}.into()),
-      false => Ok(res),
-    }
+    res.try_plus_mut(other)?;
+    Ok(res)
   }
 }
 
@@ -67,22 +167,12 @@ impl TryPlus<&CoinSet> for CoinSet {
 
   fn try_plus(&self, other: &CoinSet) -> MathResult<Self> {
     let mut res = self.clone();
-    let mut is_err = false;
-    for (denom, amount) in other.iter() {
-      res.entry(denom.clone()).and_modify(|self_amount| {
-        match (*self_amount).checked_add(*amount) {
-          Ok(amt) => *self_amount = amt,
-          Err(_) => is_err = true,
-        }
-      });
-    }
-    match is_err {
-      true => Err(ContainerError::Overflow {}.into()),
-      false => Ok(res),
-    }
+    res.try_plus_mut(other)?;
+    Ok(res)
   }
 }
 
+/// In-place [`TryPlus`]. On error `self` is left unchanged.
 pub trait TryPlusMut<T> {
   type Error;
 
@@ -93,17 +183,9 @@ impl TryPlusMut<&Coin> for CoinSet {
   type Error = MathError;
 
   fn try_plus_mut(&mut self, other: &Coin) -> MathResult {
-    let mut is_err = false;
-    self.entry(other.denom.clone()).and_modify(|amount| {
-      match (*amount).checked_sub(other.amount) {
-        Ok(amt) => *amount = amt,
-        Err(_) => is_err = true,
-      }
-    });
-    match is_err {
-      true => Err(ContainerError::Underflow {}.into()),
-      false => Ok(()),
-    }
+    plus_amount(self, &other.denom, other.amount)?;
+    prune_zeros(self);
+    Ok(())
   }
 }
 
@@ -111,22 +193,17 @@ impl TryPlusMut<&CoinSet> for CoinSet {
   type Error = MathError;
 
   fn try_plus_mut(&mut self, other: &CoinSet) -> MathResult {
-    let mut is_err = false;
+    let mut res = self.clone();
     for (denom, amount) in other.iter() {
-      self.entry(denom.clone()).and_modify(|self_amount| {
-        match (*self_amount).checked_add(*amount) {
-          Ok(amt) => *self_amount = amt,
-          Err(_) => is_err = true,
-        }
-      });
-    }
-    match is_err {
-      true => Err(ContainerError::Overflow {}.into()),
-      false => Ok(()),
+      plus_amount(&mut res, denom, *amount)?;
     }
+    prune_zeros(&mut res);
+    *self = res;
+    Ok(())
   }
 }
 
+/// Checked subtraction. For [`CoinSet`] every denom subtracted must be held in `self`.
 pub trait TryMinus<T> {
   type Output;
   type Error;
@@ -140,17 +217,8 @@ impl TryMinus<&Coin> for CoinSet {
 
   fn try_minus(&self, other: &Coin) -> MathResult<Self> {
     let mut res = self.clone();
-    let mut is_err = false;
-    res
-      .entry(other.denom.clone())
-      .and_modify(|amount| match (*amount).checked_sub(other.amount) {
-        Ok(amt) => *amount = amt,
-        Err(_) => is_err = true,
-      });
-    match is_err {
-      true => Err(ContainerError::Underflow {}.into()),
-      false => Ok(res),
-    }
+    res.try_minus_mut(other)?;
+    Ok(res)
   }
 }
 
@@ -160,22 +228,12 @@ impl TryMinus<&CoinSet> for CoinSet {
 
   fn try_minus(&self, other: &CoinSet) -> MathResult<Self> {
     let mut res = self.clone();
-    let mut is_err = false;
-    for (denom, amount) in other.iter() {
-      res.entry(denom.clone()).and_modify(|self_amount| {
-        match (*self_amount).checked_sub(*amount) {
-          Ok(amt) => *self_amount = amt,
-          Err(_) => is_err = true,
-        }
-      });
-    }
-    match is_err {
-      true => Err(ContainerError::Underflow {}.into()),
-      false => Ok(res),
-    }
+    res.try_minus_mut(other)?;
+    Ok(res)
   }
 }
 
+/// In-place [`TryMinus`]. On error `self` is left unchanged.
 pub trait TryMinusMut<T> {
   type Error;
 
@@ -186,17 +244,9 @@ impl TryMinusMut<&Coin> for CoinSet {
   type Error = MathError;
 
   fn try_minus_mut(&mut self, other: &Coin) -> MathResult {
-    let mut is_err = false;
-    self.entry(other.denom.clone()).and_modify(|amount| {
-      match (*amount).checked_sub(other.amount) {
-        Ok(amt) => *amount = amt,
-        Err(_) => is_err = true,
-      }
-    });
-    match is_err {
-      true => Err(ContainerError::Underflow {}.into()),
-      false => Ok(()),
-    }
+    minus_amount(self, &other.denom, other.amount)?;
+    prune_zeros(self);
+    Ok(())
   }
 }
 
@@ -204,18 +254,163 @@ impl TryMinusMut<&CoinSet> for CoinSet {
   type Error = MathError;
 
   fn try_minus_mut(&mut self, other: &CoinSet) -> MathResult {
-    let mut is_err = false;
+    let mut res = self.clone();
     for (denom, amount) in other.iter() {
-      self.entry(denom.clone()).and_modify(|self_amount| {
-        match (*self_amount).checked_sub(*amount) {
-          Ok(amt) => *self_amount = amt,
-          Err(_) => is_err = true,
-        }
-      });
-    }
-    match is_err {
-      true => Err(ContainerError::Underflow {}.into()),
-      false => Ok(()),
+      minus_amount(&mut res, denom, *amount)?;
     }
+    prune_zeros(&mut res);
+    *self = res;
+    Ok(())
+  }
+}
+
+/// Add `amount` of `denom`, inserting the denom if missing. Zero amounts are never inserted.
+fn plus_amount(coins: &mut CoinSet, denom: &str, amount: Uint128) -> MathResult {
+  if amount.is_zero() {
+    return Ok(());
+  }
+  let total = coins.entry(denom.to_string()).or_default();
+  *total = total.checked_add(amount)?;
+  Ok(())
+}
+
+/// Subtract `amount` of `denom`. Requires the denom to be held unless `amount` is zero.
+fn minus_amount(coins: &mut CoinSet, denom: &str, amount: Uint128) -> MathResult {
+  if amount.is_zero() {
+    return Ok(());
+  }
+  let total = coins.get_mut(denom).ok_or(ContainerError::Underflow {})?;
+  *total = total
+    .checked_sub(amount)
+    .map_err(|_| ContainerError::Underflow {})?;
+  Ok(())
+}
+
+fn prune_zeros(coins: &mut CoinSet) {
+  coins.retain(|_, amount| !amount.is_zero());
+}
+
+/// Checked multiplication.
+///
+/// Multiplying by an integer is exact. Multiplying by a `(Decimal, Rounding)` pair rounds the
+/// product as given.
+pub trait TryMul<T> {
+  type Output;
+  type Error;
+
+  fn try_mul(&self, other: T) -> Result<Self::Output, Self::Error>;
+}
+
+/// Checked division.
+pub trait TryDiv<T> {
+  type Output;
+  type Error;
+
+  fn try_div(&self, other: T, rounding: Rounding) -> Result<Self::Output, Self::Error>;
+}
+
+/// Checked multiplication by `numerator / denominator`, without overflow of the intermediate
+/// product.
+pub trait TryMulRatio<T> {
+  type Output;
+  type Error;
+
+  fn try_mul_ratio(
+    &self,
+    numerator: T,
+    denominator: T,
+    rounding: Rounding,
+  ) -> Result<Self::Output, Self::Error>;
+}
+
+impl TryMul<Uint128> for Coin {
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_mul(&self, other: Uint128) -> MathResult<Self> {
+    Ok(Coin::new(self.amount.checked_mul(other)?, &self.denom))
+  }
+}
+
+impl TryMul<(Decimal, Rounding)> for Coin {
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_mul(&self, (other, rounding): (Decimal, Rounding)) -> MathResult<Self> {
+    self.try_mul_ratio(
+      other.atomics(),
+      Uint128::new(10u128.pow(Decimal::DECIMAL_PLACES)),
+      rounding,
+    )
+  }
+}
+
+impl TryDiv<Uint128> for Coin {
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_div(&self, other: Uint128, rounding: Rounding) -> MathResult<Self> {
+    self.try_mul_ratio(Uint128::one(), other, rounding)
+  }
+}
+
+impl TryMulRatio<Uint128> for Coin {
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_mul_ratio(
+    &self,
+    numerator: Uint128,
+    denominator: Uint128,
+    rounding: Rounding,
+  ) -> MathResult<Self> {
+    Ok(Coin::new(
+      self.amount.mul_div(numerator, denominator, rounding)?,
+      &self.denom,
+    ))
+  }
+}
+
+impl<T: Copy> TryMul<T> for CoinSet
+where Coin: TryMul<T, Output=Coin, Error=MathError>
+{
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_mul(&self, other: T) -> MathResult<Self> {
+    scale(self, |coin| coin.try_mul(other))
+  }
+}
+
+impl<T: Copy> TryDiv<T> for CoinSet
+where Coin: TryDiv<T, Output=Coin, Error=MathError>
+{
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_div(&self, other: T, rounding: Rounding) -> MathResult<Self> {
+    scale(self, |coin| coin.try_div(other, rounding))
+  }
+}
+
+impl<T: Copy> TryMulRatio<T> for CoinSet
+where Coin: TryMulRatio<T, Output=Coin, Error=MathError>
+{
+  type Output = Self;
+  type Error = MathError;
+
+  fn try_mul_ratio(&self, numerator: T, denominator: T, rounding: Rounding) -> MathResult<Self> {
+    scale(self, |coin| {
+      coin.try_mul_ratio(numerator, denominator, rounding)
+    })
+  }
+}
+
+/// Scale each coin in `coins`, pruning any that scale to zero.
+fn scale(coins: &CoinSet, f: impl Fn(Coin) -> MathResult<Coin>) -> MathResult<CoinSet> {
+  let mut res = CoinSet::default();
+  for (denom, amount) in coins.iter() {
+    plus_amount(&mut res, denom, f(Coin::new(*amount, denom))?.amount)?;
   }
+  Ok(res)
 }
@@ -0,0 +1,196 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, BlockInfo, Event, Storage};
+use cw_storage_plus::Item;
+
+use crate::{AuthError, Authorized, Expiry, XcosmResult};
+
+/// Type of the events emitted by [`Ownership`].
+pub const OWNERSHIP_EVENT: &str = "ownership";
+
+/// Owner transfer awaiting acceptance by the proposed owner.
+#[cw_serde]
+pub struct PendingOwner {
+  /// Address which may accept ownership.
+  pub address: Addr,
+  /// Expiry after which the transfer can no longer be accepted.
+  pub expiry: Expiry,
+}
+
+/// Current owner and any pending transfer.
+#[cw_serde]
+#[derive(Default)]
+pub struct OwnershipState {
+  /// Owner, or none once ownership is renounced.
+  pub owner: Option<Addr>,
+  /// Proposed transfer of ownership.
+  pub pending: Option<PendingOwner>,
+}
+
+impl OwnershipState {
+  /// Owner as an [`Authorized`] group.
+  pub fn authorized(&self) -> Authorized {
+    match &self.owner {
+      Some(owner) => Authorized::One(owner.clone()),
+      None => Authorized::None,
+    }
+  }
+}
+
+/// Ownership execute messages to embed in a contract's execute message.
+#[cw_serde]
+pub enum OwnershipExecuteMsg {
+  /// Propose a new owner, replacing any pending transfer. Only the owner may propose.
+  ProposeTransfer { new_owner: String, expiry: Expiry },
+  /// Accept a pending transfer. Only the pending owner may accept, before the expiry.
+  AcceptTransfer {},
+  /// Cancel a pending transfer. Only the owner may cancel.
+  CancelTransfer {},
+  /// Give up ownership for good. Only the owner may renounce.
+  Renounce {},
+}
+
+/// Ownership query messages to embed in a contract's query message.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OwnershipQueryMsg {
+  /// Get the owner and any pending transfer.
+  #[returns(OwnershipState)]
+  Ownership {},
+}
+
+/// Storage-backed contract owner with two-step transfers.
+///
+/// A transfer is proposed by the owner and only takes effect once accepted by the proposed
+/// owner, so ownership cannot be handed to an address which is unable to use it.
+pub struct Ownership {
+  namespace: &'static str,
+}
+
+impl Ownership {
+  /// Create new [`Ownership`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self { namespace }
+  }
+
+  fn state(&self) -> Item<OwnershipState> {
+    Item::new(self.namespace)
+  }
+
+  /// Set the owner without authorization, e.g. on instantiation, clearing any pending
+  /// transfer.
+  pub fn initialize(&self, storage: &mut dyn Storage, owner: Addr) -> XcosmResult<Event> {
+    self.state().save(
+      storage,
+      &OwnershipState {
+        owner: Some(owner.clone()),
+        pending: None,
+      },
+    )?;
+    Ok(event("initialize").add_attribute("owner", owner))
+  }
+
+  /// Load the owner and any pending transfer.
+  pub fn load(&self, storage: &dyn Storage) -> XcosmResult<OwnershipState> {
+    Ok(self.state().may_load(storage)?.unwrap_or_default())
+  }
+
+  /// Require `sender` to be the owner, returning the loaded state.
+  pub fn require_owner(&self, storage: &dyn Storage, sender: &Addr) -> XcosmResult<OwnershipState> {
+    let state = self.load(storage)?;
+    if state.owner.as_ref() != Some(sender) {
+      return Err(AuthError::NotOwner {}.into());
+    }
+    Ok(state)
+  }
+
+  /// Propose `new_owner` as owner until `expiry`, replacing any pending transfer.
+  ///
+  /// Requires `sender` to be the owner and `expiry` to not be reached at `block`.
+  pub fn propose_transfer(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    new_owner: Addr,
+    expiry: Expiry,
+    block: &BlockInfo,
+  ) -> XcosmResult<Event> {
+    let mut state = self.require_owner(storage, sender)?;
+    if expiry.is_expired(block) {
+      return Err(AuthError::TransferExpired { expiry }.into());
+    }
+    let event = event("propose_transfer")
+      .add_attribute("owner", sender)
+      .add_attribute("pending_owner", &new_owner)
+      .add_attribute("expiry", expiry.to_string());
+    state.pending = Some(PendingOwner {
+      address: new_owner,
+      expiry,
+    });
+    self.state().save(storage, &state)?;
+    Ok(event)
+  }
+
+  /// Accept the pending transfer, making `sender` the owner.
+  ///
+  /// Requires `sender` to be the pending owner and the transfer to not be expired at `block`.
+  pub fn accept_transfer(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    block: &BlockInfo,
+  ) -> XcosmResult<Event> {
+    let mut state = self.load(storage)?;
+    let pending = state.pending.take().ok_or(AuthError::NoPendingOwner {})?;
+    if pending.address != *sender {
+      return Err(AuthError::NotPendingOwner {}.into());
+    }
+    if pending.expiry.is_expired(block) {
+      return Err(
+        AuthError::TransferExpired {
+          expiry: pending.expiry,
+        }
+        .into(),
+      );
+    }
+    let event = event("accept_transfer")
+      .add_attribute("previous_owner", owner_attribute(&state.owner))
+      .add_attribute("owner", sender);
+    state.owner = Some(pending.address);
+    self.state().save(storage, &state)?;
+    Ok(event)
+  }
+
+  /// Cancel the pending transfer.
+  ///
+  /// Requires `sender` to be the owner and a transfer to be pending.
+  pub fn cancel_transfer(&self, storage: &mut dyn Storage, sender: &Addr) -> XcosmResult<Event> {
+    let mut state = self.require_owner(storage, sender)?;
+    let pending = state.pending.take().ok_or(AuthError::NoPendingOwner {})?;
+    self.state().save(storage, &state)?;
+    Ok(
+      event("cancel_transfer")
+        .add_attribute("owner", sender)
+        .add_attribute("pending_owner", pending.address),
+    )
+  }
+
+  /// Give up ownership, leaving the contract without an owner and clearing any pending
+  /// transfer.
+  ///
+  /// Requires `sender` to be the owner.
+  pub fn renounce(&self, storage: &mut dyn Storage, sender: &Addr) -> XcosmResult<Event> {
+    self.require_owner(storage, sender)?;
+    self.state().save(storage, &OwnershipState::default())?;
+    Ok(event("renounce").add_attribute("previous_owner", sender))
+  }
+}
+
+fn event(action: &str) -> Event {
+  Event::new(OWNERSHIP_EVENT).add_attribute("action", action)
+}
+
+fn owner_attribute(owner: &Option<Addr>) -> String {
+  owner
+    .as_ref()
+    .map_or_else(|| "none".to_string(), Addr::to_string)
+}
@@ -0,0 +1,40 @@
+use cosmwasm_std::{Order, Storage};
+use cw_storage_plus::{Bound, KeyDeserialize, Map, PrimaryKey};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::XcosmResult;
+
+/// Default number of entries returned by a paginated query.
+pub const DEFAULT_PAGE_LIMIT: u32 = 10;
+
+/// Maximum number of entries returned by a paginated query.
+pub const MAX_PAGE_LIMIT: u32 = 30;
+
+/// Load a page of `map` sorted by key, starting after `start_after`.
+///
+/// Returns at most `limit` entries, [`DEFAULT_PAGE_LIMIT`] if unset and never more than
+/// [`MAX_PAGE_LIMIT`].
+pub fn paginate<'a, K, T>(
+  storage: &dyn Storage,
+  map: &Map<K, T>,
+  start_after: Option<K>,
+  limit: Option<u32>,
+) -> XcosmResult<Vec<(K::Output, T)>>
+where
+  K: PrimaryKey<'a>+KeyDeserialize,
+  K::Output: 'static,
+  T: Serialize+DeserializeOwned,
+{
+  let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+  Ok(
+    map
+      .range(
+        storage,
+        start_after.map(Bound::exclusive),
+        None,
+        Order::Ascending,
+      )
+      .take(limit)
+      .collect::<Result<Vec<_>, _>>()?,
+  )
+}
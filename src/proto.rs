@@ -0,0 +1,405 @@
+use cosmwasm_std::{Addr, AnyMsg, Coin, Timestamp, Uint128};
+
+use crate::{BankMsgIo, BankMsgMultiSend, XcosmResult};
+
+pub type ProtoResult<T=()> = Result<T, ProtoError>;
+
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum ProtoError {
+  #[error("Protobuf message ended unexpectedly")]
+  Truncated {},
+
+  #[error("Protobuf varint is too long")]
+  VarintOverflow {},
+
+  #[error("Unsupported protobuf wire type {wire_type}")]
+  WireType { wire_type: u64 },
+
+  #[error("Invalid protobuf field {field}: {reason}")]
+  InvalidField { field: u32, reason: String },
+
+  #[error("Unexpected protobuf type url: expected {expected:?}, got {actual:?}")]
+  TypeUrl { expected: String, actual: String },
+}
+
+/// Protobuf wire types. See [encoding](https://protobuf.dev/programming-guides/encoding/#structure).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireType {
+  Varint,
+  Fixed64,
+  Len,
+  Fixed32,
+}
+
+impl TryFrom<u64> for WireType {
+  type Error = ProtoError;
+
+  fn try_from(wire_type: u64) -> ProtoResult<Self> {
+    match wire_type {
+      0 => Ok(WireType::Varint),
+      1 => Ok(WireType::Fixed64),
+      2 => Ok(WireType::Len),
+      5 => Ok(WireType::Fixed32),
+      _ => Err(ProtoError::WireType { wire_type }),
+    }
+  }
+}
+
+impl From<WireType> for u64 {
+  fn from(wire_type: WireType) -> Self {
+    match wire_type {
+      WireType::Varint => 0,
+      WireType::Fixed64 => 1,
+      WireType::Len => 2,
+      WireType::Fixed32 => 5,
+    }
+  }
+}
+
+/// Protobuf message encoding.
+pub trait ProtoMessage: Sized {
+  /// Append the encoded message to `buf`.
+  fn encode_to(&self, buf: &mut ProtoWriter);
+
+  /// Decode the message from a reader over its encoded fields.
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self>;
+
+  /// Encode the message.
+  fn encode(&self) -> Vec<u8> {
+    let mut writer = ProtoWriter::default();
+    self.encode_to(&mut writer);
+    writer.into_bytes()
+  }
+
+  /// Decode the message.
+  fn decode(bytes: &[u8]) -> ProtoResult<Self> {
+    Self::decode_from(&mut ProtoReader::new(bytes))
+  }
+}
+
+/// Protobuf message which can be sent as a [`CosmosMsg::Any`](cosmwasm_std::CosmosMsg::Any).
+pub trait ProtoAny: ProtoMessage {
+  /// Fully qualified protobuf type url, e.g. `/cosmos.bank.v1beta1.MsgMultiSend`.
+  const TYPE_URL: &'static str;
+
+  /// Encode the message into an [`AnyMsg`].
+  fn into_any_msg(self) -> AnyMsg {
+    AnyMsg {
+      type_url: Self::TYPE_URL.to_string(),
+      value: self.encode().into(),
+    }
+  }
+
+  /// Encode the message into a [`CosmosMsg::Any`](cosmwasm_std::CosmosMsg::Any), or a
+  /// [`CosmosMsg::Stargate`](cosmwasm_std::CosmosMsg::Stargate) without the `cosmwasm_2_0`
+  /// feature.
+  #[cfg(any(feature = "cosmwasm_2_0", feature = "stargate"))]
+  fn into_cosmos_msg<T>(self) -> cosmwasm_std::CosmosMsg<T> {
+    let any = self.into_any_msg();
+    #[cfg(feature = "cosmwasm_2_0")]
+    return cosmwasm_std::CosmosMsg::Any(any);
+    #[cfg(not(feature = "cosmwasm_2_0"))]
+    #[allow(deprecated)]
+    return cosmwasm_std::CosmosMsg::Stargate {
+      type_url: any.type_url,
+      value: any.value,
+    };
+  }
+
+  /// Decode the message from an [`AnyMsg`].
+  ///
+  /// Requires the type url to match.
+  fn from_any_msg(msg: &AnyMsg) -> XcosmResult<Self> {
+    if msg.type_url != Self::TYPE_URL {
+      return Err(
+        ProtoError::TypeUrl {
+          expected: Self::TYPE_URL.to_string(),
+          actual: msg.type_url.clone(),
+        }
+        .into(),
+      );
+    }
+    Ok(Self::decode(msg.value.as_slice())?)
+  }
+}
+
+/// Protobuf encoder. Fields holding default values are omitted, as in proto3.
+#[derive(Debug, Clone, Default)]
+pub struct ProtoWriter(Vec<u8>);
+
+impl ProtoWriter {
+  pub fn into_bytes(self) -> Vec<u8> {
+    self.0
+  }
+
+  pub fn varint(&mut self, mut value: u64) {
+    while value >= 0x80 {
+      self.0.push((value as u8 & 0x7f) | 0x80);
+      value >>= 7;
+    }
+    self.0.push(value as u8);
+  }
+
+  pub fn key(&mut self, field: u32, wire_type: WireType) {
+    self.varint(((field as u64) << 3) | u64::from(wire_type));
+  }
+
+  pub fn uint64(&mut self, field: u32, value: u64) {
+    if value != 0 {
+      self.key(field, WireType::Varint);
+      self.varint(value);
+    }
+  }
+
+  pub fn bool(&mut self, field: u32, value: bool) {
+    self.uint64(field, value as u64);
+  }
+
+  pub fn bytes(&mut self, field: u32, value: &[u8]) {
+    if !value.is_empty() {
+      self.key(field, WireType::Len);
+      self.varint(value.len() as u64);
+      self.0.extend_from_slice(value);
+    }
+  }
+
+  pub fn string(&mut self, field: u32, value: &str) {
+    self.bytes(field, value.as_bytes());
+  }
+
+  /// Write a nested message. Unlike scalars, nested messages are written even when empty.
+  pub fn message(&mut self, field: u32, value: &impl ProtoMessage) {
+    let encoded = value.encode();
+    self.key(field, WireType::Len);
+    self.varint(encoded.len() as u64);
+    self.0.extend_from_slice(&encoded);
+  }
+
+  /// Write a nested message if set.
+  pub fn optional_message(&mut self, field: u32, value: &Option<impl ProtoMessage>) {
+    if let Some(value) = value {
+      self.message(field, value);
+    }
+  }
+
+  /// Write each message of a repeated field.
+  pub fn messages<'a, T: ProtoMessage+'a>(
+    &mut self,
+    field: u32,
+    values: impl IntoIterator<Item=&'a T>,
+  ) {
+    for value in values {
+      self.message(field, value);
+    }
+  }
+}
+
+/// Protobuf decoder. Unknown fields are skipped.
+#[derive(Debug, Clone)]
+pub struct ProtoReader<'a> {
+  bytes: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+  pub fn new(bytes: &'a [u8]) -> Self {
+    Self { bytes, pos: 0 }
+  }
+
+  /// Read the next field key, or `None` at the end of the message.
+  pub fn next_field(&mut self) -> ProtoResult<Option<(u32, WireType)>> {
+    if self.pos >= self.bytes.len() {
+      return Ok(None);
+    }
+    let key = self.varint()?;
+    let field = u32::try_from(key >> 3).map_err(|_| ProtoError::InvalidField {
+      field: u32::MAX,
+      reason: "field number out of range".to_string(),
+    })?;
+    Ok(Some((field, WireType::try_from(key & 0x7)?)))
+  }
+
+  pub fn varint(&mut self) -> ProtoResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+      let byte = *self.bytes.get(self.pos).ok_or(ProtoError::Truncated {})?;
+      self.pos += 1;
+      value |= ((byte & 0x7f) as u64) << shift;
+      if byte & 0x80 == 0 {
+        return Ok(value);
+      }
+    }
+    Err(ProtoError::VarintOverflow {})
+  }
+
+  pub fn bytes(&mut self) -> ProtoResult<&'a [u8]> {
+    let len = self.varint()? as usize;
+    let end = self.pos.checked_add(len).ok_or(ProtoError::Truncated {})?;
+    let bytes = self
+      .bytes
+      .get(self.pos..end)
+      .ok_or(ProtoError::Truncated {})?;
+    self.pos = end;
+    Ok(bytes)
+  }
+
+  pub fn string(&mut self, field: u32) -> ProtoResult<String> {
+    String::from_utf8(self.bytes()?.to_vec()).map_err(|err| ProtoError::InvalidField {
+      field,
+      reason: err.to_string(),
+    })
+  }
+
+  pub fn message<T: ProtoMessage>(&mut self) -> ProtoResult<T> {
+    T::decode(self.bytes()?)
+  }
+
+  /// Skip a field of `wire_type`.
+  pub fn skip(&mut self, wire_type: WireType) -> ProtoResult {
+    let len = match wire_type {
+      WireType::Varint => return self.varint().map(|_| ()),
+      WireType::Len => return self.bytes().map(|_| ()),
+      WireType::Fixed64 => 8,
+      WireType::Fixed32 => 4,
+    };
+    self.pos = self
+      .pos
+      .checked_add(len)
+      .filter(|&end| end <= self.bytes.len())
+      .ok_or(ProtoError::Truncated {})?;
+    Ok(())
+  }
+}
+
+/// `google.protobuf.Any`.
+impl ProtoMessage for AnyMsg {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.type_url);
+    buf.bytes(2, self.value.as_slice());
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = AnyMsg {
+      type_url: String::new(),
+      value: Default::default(),
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.type_url = reader.string(field)?,
+        2 => msg.value = reader.bytes()?.to_vec().into(),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+/// `google.protobuf.Timestamp`.
+impl ProtoMessage for Timestamp {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.uint64(1, self.seconds());
+    buf.uint64(2, self.subsec_nanos());
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut seconds = 0u64;
+    let mut nanos = 0u64;
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => seconds = reader.varint()?,
+        2 => nanos = reader.varint()?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    // Negative values are encoded as 10 byte varints, so they fail these checks too.
+    if nanos >= 1_000_000_000 {
+      return Err(ProtoError::InvalidField {
+        field: 2,
+        reason: "nanos out of range".to_string(),
+      });
+    }
+    seconds
+      .checked_mul(1_000_000_000)
+      .and_then(|seconds_nanos| seconds_nanos.checked_add(nanos))
+      .map(Timestamp::from_nanos)
+      .ok_or_else(|| ProtoError::InvalidField {
+        field: 1,
+        reason: "timestamp out of range".to_string(),
+      })
+  }
+}
+
+/// `cosmos.base.v1beta1.Coin`.
+impl ProtoMessage for Coin {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.denom);
+    buf.string(2, &self.amount.to_string());
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut coin = Coin::new(Uint128::zero(), "");
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => coin.denom = reader.string(field)?,
+        2 => {
+          coin.amount = reader
+            .string(field)?
+            .parse()
+            .map_err(|_| ProtoError::InvalidField {
+              field,
+              reason: "invalid coin amount".to_string(),
+            })?
+        }
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(coin)
+  }
+}
+
+/// `cosmos.bank.v1beta1.Input` and `cosmos.bank.v1beta1.Output`.
+impl ProtoMessage for BankMsgIo {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, self.address().as_str());
+    buf.messages(2, self.coins());
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut address = String::new();
+    let mut coins = vec![];
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => address = reader.string(field)?,
+        2 => coins.push(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(BankMsgIo::new(Addr::unchecked(address), coins))
+  }
+}
+
+impl ProtoMessage for BankMsgMultiSend {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.messages(1, &self.inputs);
+    buf.messages(2, &self.outputs);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = BankMsgMultiSend {
+      inputs: vec![],
+      outputs: vec![],
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.inputs.push(reader.message()?),
+        2 => msg.outputs.push(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for BankMsgMultiSend {
+  const TYPE_URL: &'static str = "/cosmos.bank.v1beta1.MsgMultiSend";
+}
@@ -0,0 +1,247 @@
+use std::marker::PhantomData;
+
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Storage};
+use cw_storage_plus::Map;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{paginate, AuthError, Authorized, XcosmResult};
+
+/// Members of a role and the role which administers it.
+#[cw_serde]
+pub struct Role<R> {
+  pub role: R,
+  /// Addresses holding the role.
+  pub members: Authorized,
+  /// Role whose members may grant and revoke this role. Without an admin, membership can only
+  /// be changed by the contract through [`Roles::set_role`].
+  pub admin: Option<R>,
+}
+
+/// Role execute messages to embed in a contract's execute message.
+#[cw_serde]
+pub enum RoleExecuteMsg<R> {
+  /// Grant a role to an address. Only members of the role's admin role may grant it.
+  Grant { role: R, address: String },
+  /// Revoke a role from an address. Only members of the role's admin role may revoke it.
+  Revoke { role: R, address: String },
+  /// Give up a role held by the sender.
+  Renounce { role: R },
+  /// Change the admin role of a role. Only members of the current admin role may change it.
+  SetAdmin { role: R, admin: Option<R> },
+}
+
+/// Role query messages to embed in a contract's query message.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RoleQueryMsg<R> {
+  /// Get a role's members and admin role.
+  #[returns(Role<R>)]
+  Role { role: R },
+  /// Check whether an address holds a role.
+  #[returns(HasRoleResponse)]
+  HasRole { role: R, address: String },
+  /// List roles sorted by role key, starting after `start_after`.
+  #[returns(RolesResponse<R>)]
+  Roles {
+    start_after: Option<R>,
+    limit: Option<u32>,
+  },
+}
+
+/// Whether an address holds a role.
+#[cw_serde]
+pub struct HasRoleResponse {
+  pub has_role: bool,
+}
+
+/// Page of roles, sorted by role key.
+#[cw_serde]
+pub struct RolesResponse<R> {
+  pub roles: Vec<Role<R>>,
+}
+
+/// Storage-backed groups of [`Authorized`] addresses keyed by role.
+///
+/// Roles are stored under the string form of `R`, which must be unique per role. A role which
+/// was never set has no members and no admin.
+pub struct Roles<R> {
+  namespace: &'static str,
+  role: PhantomData<R>,
+}
+
+impl<R: Clone+ToString+Serialize+DeserializeOwned> Roles<R> {
+  /// Create new [`Roles`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self {
+      namespace,
+      role: PhantomData,
+    }
+  }
+
+  fn roles<'a>(&self) -> Map<&'a str, Role<R>> {
+    Map::new(self.namespace)
+  }
+
+  /// Set a role's members and admin role without authorization, e.g. on instantiation.
+  pub fn set_role(
+    &self,
+    storage: &mut dyn Storage,
+    role: R,
+    members: Authorized,
+    admin: Option<R>,
+  ) -> XcosmResult {
+    let key = role.to_string();
+    self.roles().save(
+      storage,
+      &key,
+      &Role {
+        role,
+        members,
+        admin,
+      },
+    )?;
+    Ok(())
+  }
+
+  /// Load a role's members and admin role.
+  pub fn load(&self, storage: &dyn Storage, role: &R) -> XcosmResult<Role<R>> {
+    Ok(
+      self
+        .roles()
+        .may_load(storage, &role.to_string())?
+        .unwrap_or_else(|| Role {
+          role: role.clone(),
+          members: Authorized::None,
+          admin: None,
+        }),
+    )
+  }
+
+  /// Whether `addr` holds `role`.
+  pub fn has_role(&self, storage: &dyn Storage, role: &R, addr: &Addr) -> XcosmResult<bool> {
+    Ok(self.load(storage, role)?.members.authorize(addr).is_ok())
+  }
+
+  /// Require `addr` to hold `role`.
+  pub fn require_role(&self, storage: &dyn Storage, role: &R, addr: &Addr) -> XcosmResult {
+    match self.has_role(storage, role, addr)? {
+      true => Ok(()),
+      false => Err(
+        AuthError::MissingRole {
+          role: role.to_string(),
+        }
+        .into(),
+      ),
+    }
+  }
+
+  /// Require `sender` to hold the admin role of `role`, returning the loaded role.
+  fn load_as_admin(&self, storage: &dyn Storage, sender: &Addr, role: &R) -> XcosmResult<Role<R>> {
+    let loaded = self.load(storage, role)?;
+    match &loaded.admin {
+      Some(admin) => self.require_role(storage, admin, sender)?,
+      None => {
+        return Err(
+          AuthError::NoRoleAdmin {
+            role: role.to_string(),
+          }
+          .into(),
+        )
+      }
+    }
+    Ok(loaded)
+  }
+
+  /// Grant `role` to `addr`, returning whether it was newly granted.
+  ///
+  /// Requires `sender` to hold the admin role of `role`.
+  pub fn grant(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &R,
+    addr: Addr,
+  ) -> XcosmResult<bool> {
+    let mut loaded = self.load_as_admin(storage, sender, role)?;
+    let granted = loaded.members.insert(addr);
+    self.roles().save(storage, &role.to_string(), &loaded)?;
+    Ok(granted)
+  }
+
+  /// Revoke `role` from `addr`, returning whether it was held.
+  ///
+  /// Requires `sender` to hold the admin role of `role`.
+  pub fn revoke(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &R,
+    addr: &Addr,
+  ) -> XcosmResult<bool> {
+    let mut loaded = self.load_as_admin(storage, sender, role)?;
+    let revoked = loaded.members.remove(addr);
+    self.roles().save(storage, &role.to_string(), &loaded)?;
+    Ok(revoked)
+  }
+
+  /// Give up `role` held by `sender`.
+  ///
+  /// Requires `sender` to hold `role` as a listed member.
+  pub fn renounce(&self, storage: &mut dyn Storage, sender: &Addr, role: &R) -> XcosmResult {
+    let mut loaded = self.load(storage, role)?;
+    if !loaded.members.remove(sender) {
+      return Err(
+        AuthError::MissingRole {
+          role: role.to_string(),
+        }
+        .into(),
+      );
+    }
+    self.roles().save(storage, &role.to_string(), &loaded)?;
+    Ok(())
+  }
+
+  /// Change the admin role of `role`.
+  ///
+  /// Requires `sender` to hold the current admin role of `role`.
+  pub fn set_admin(
+    &self,
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    role: &R,
+    admin: Option<R>,
+  ) -> XcosmResult {
+    let mut loaded = self.load_as_admin(storage, sender, role)?;
+    loaded.admin = admin;
+    self.roles().save(storage, &role.to_string(), &loaded)?;
+    Ok(())
+  }
+
+  /// Query whether `addr` holds `role`.
+  pub fn query_has_role(
+    &self,
+    storage: &dyn Storage,
+    role: &R,
+    addr: &Addr,
+  ) -> XcosmResult<HasRoleResponse> {
+    Ok(HasRoleResponse {
+      has_role: self.has_role(storage, role, addr)?,
+    })
+  }
+
+  /// Query a page of roles sorted by role key, starting after `start_after`.
+  pub fn list(
+    &self,
+    storage: &dyn Storage,
+    start_after: Option<&R>,
+    limit: Option<u32>,
+  ) -> XcosmResult<RolesResponse<R>> {
+    let start = start_after.map(ToString::to_string);
+    let roles = paginate(storage, &self.roles(), start.as_deref(), limit)?
+      .into_iter()
+      .map(|(_, role)| role)
+      .collect();
+    Ok(RolesResponse { roles })
+  }
+}
@@ -0,0 +1,618 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{AnyMsg, Coin, Timestamp};
+
+use crate::{ProtoAny, ProtoError, ProtoMessage, ProtoReader, ProtoResult, ProtoWriter};
+
+/// Delegate `amount` from `delegator_address` to `validator_address`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/staking/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgDelegate {
+  pub delegator_address: String,
+  pub validator_address: String,
+  pub amount: Coin,
+}
+
+/// Undelegate `amount` of `delegator_address` from `validator_address`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/staking/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgUndelegate {
+  pub delegator_address: String,
+  pub validator_address: String,
+  pub amount: Coin,
+}
+
+/// Redelegate `amount` of `delegator_address` between validators.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/staking/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgBeginRedelegate {
+  pub delegator_address: String,
+  pub validator_src_address: String,
+  pub validator_dst_address: String,
+  pub amount: Coin,
+}
+
+/// Set the address receiving `delegator_address`'s staking rewards.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/distribution/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgSetWithdrawAddress {
+  pub delegator_address: String,
+  pub withdraw_address: String,
+}
+
+/// Withdraw `delegator_address`'s staking rewards from `validator_address`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/distribution/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgWithdrawDelegatorReward {
+  pub delegator_address: String,
+  pub validator_address: String,
+}
+
+/// Fund the community pool with `amount` from `depositor`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/distribution/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgFundCommunityPool {
+  pub amount: Vec<Coin>,
+  pub depositor: String,
+}
+
+/// Authorization to execute any message of type `msg`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/authz/v1beta1/authz.proto).
+#[cw_serde]
+pub struct GenericAuthorization {
+  pub msg: String,
+}
+
+/// Authorization and its optional expiration.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/authz/v1beta1/authz.proto).
+#[cw_serde]
+pub struct Grant {
+  pub authorization: Option<AnyMsg>,
+  pub expiration: Option<Timestamp>,
+}
+
+/// Grant an authorization from `granter` to `grantee`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/authz/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgGrant {
+  pub granter: String,
+  pub grantee: String,
+  pub grant: Grant,
+}
+
+/// Execute `msgs` as `grantee` using its authorizations.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/authz/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgExec {
+  pub grantee: String,
+  pub msgs: Vec<AnyMsg>,
+}
+
+/// Revoke `granter`'s authorization of `msg_type_url` for `grantee`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/authz/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgRevoke {
+  pub granter: String,
+  pub grantee: String,
+  pub msg_type_url: String,
+}
+
+/// Fee allowance with an optional spend limit and expiration.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/feegrant/v1beta1/feegrant.proto).
+#[cw_serde]
+pub struct BasicAllowance {
+  pub spend_limit: Vec<Coin>,
+  pub expiration: Option<Timestamp>,
+}
+
+/// Grant a fee allowance from `granter` to `grantee`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/feegrant/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgGrantAllowance {
+  pub granter: String,
+  pub grantee: String,
+  pub allowance: Option<AnyMsg>,
+}
+
+/// Revoke `granter`'s fee allowance for `grantee`.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/feegrant/v1beta1/tx.proto).
+#[cw_serde]
+pub struct MsgRevokeAllowance {
+  pub granter: String,
+  pub grantee: String,
+}
+
+/// Governance vote option.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/gov/v1/gov.proto).
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum VoteOption {
+  #[default]
+  Unspecified,
+  Yes,
+  Abstain,
+  No,
+  NoWithVeto,
+}
+
+impl From<VoteOption> for u64 {
+  fn from(option: VoteOption) -> Self {
+    match option {
+      VoteOption::Unspecified => 0,
+      VoteOption::Yes => 1,
+      VoteOption::Abstain => 2,
+      VoteOption::No => 3,
+      VoteOption::NoWithVeto => 4,
+    }
+  }
+}
+
+impl TryFrom<u64> for VoteOption {
+  type Error = ProtoError;
+
+  fn try_from(option: u64) -> ProtoResult<Self> {
+    match option {
+      0 => Ok(VoteOption::Unspecified),
+      1 => Ok(VoteOption::Yes),
+      2 => Ok(VoteOption::Abstain),
+      3 => Ok(VoteOption::No),
+      4 => Ok(VoteOption::NoWithVeto),
+      _ => Err(ProtoError::InvalidField {
+        field: 3,
+        reason: format!("unknown vote option {}", option),
+      }),
+    }
+  }
+}
+
+/// Vote on a governance proposal.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/gov/v1/tx.proto).
+#[cw_serde]
+pub struct MsgVote {
+  pub proposal_id: u64,
+  pub voter: String,
+  pub option: VoteOption,
+  pub metadata: String,
+}
+
+/// Deposit `amount` on a governance proposal.
+/// See [protobuf definition](https://github.com/cosmos/cosmos-sdk/blob/v0.47.0/proto/cosmos/gov/v1/tx.proto).
+#[cw_serde]
+pub struct MsgDeposit {
+  pub proposal_id: u64,
+  pub depositor: String,
+  pub amount: Vec<Coin>,
+}
+
+impl ProtoMessage for MsgDelegate {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.delegator_address);
+    buf.string(2, &self.validator_address);
+    buf.message(3, &self.amount);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let (delegator_address, validator_address, amount) = decode_delegation(reader)?;
+    Ok(MsgDelegate {
+      delegator_address,
+      validator_address,
+      amount,
+    })
+  }
+}
+
+impl ProtoAny for MsgDelegate {
+  const TYPE_URL: &'static str = "/cosmos.staking.v1beta1.MsgDelegate";
+}
+
+impl ProtoMessage for MsgUndelegate {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.delegator_address);
+    buf.string(2, &self.validator_address);
+    buf.message(3, &self.amount);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let (delegator_address, validator_address, amount) = decode_delegation(reader)?;
+    Ok(MsgUndelegate {
+      delegator_address,
+      validator_address,
+      amount,
+    })
+  }
+}
+
+impl ProtoAny for MsgUndelegate {
+  const TYPE_URL: &'static str = "/cosmos.staking.v1beta1.MsgUndelegate";
+}
+
+impl ProtoMessage for MsgBeginRedelegate {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.delegator_address);
+    buf.string(2, &self.validator_src_address);
+    buf.string(3, &self.validator_dst_address);
+    buf.message(4, &self.amount);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgBeginRedelegate {
+      delegator_address: String::new(),
+      validator_src_address: String::new(),
+      validator_dst_address: String::new(),
+      amount: empty_coin(),
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.delegator_address = reader.string(field)?,
+        2 => msg.validator_src_address = reader.string(field)?,
+        3 => msg.validator_dst_address = reader.string(field)?,
+        4 => msg.amount = reader.message()?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgBeginRedelegate {
+  const TYPE_URL: &'static str = "/cosmos.staking.v1beta1.MsgBeginRedelegate";
+}
+
+impl ProtoMessage for MsgSetWithdrawAddress {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.delegator_address);
+    buf.string(2, &self.withdraw_address);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let (delegator_address, withdraw_address) = decode_string_pair(reader)?;
+    Ok(MsgSetWithdrawAddress {
+      delegator_address,
+      withdraw_address,
+    })
+  }
+}
+
+impl ProtoAny for MsgSetWithdrawAddress {
+  const TYPE_URL: &'static str = "/cosmos.distribution.v1beta1.MsgSetWithdrawAddress";
+}
+
+impl ProtoMessage for MsgWithdrawDelegatorReward {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.delegator_address);
+    buf.string(2, &self.validator_address);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let (delegator_address, validator_address) = decode_string_pair(reader)?;
+    Ok(MsgWithdrawDelegatorReward {
+      delegator_address,
+      validator_address,
+    })
+  }
+}
+
+impl ProtoAny for MsgWithdrawDelegatorReward {
+  const TYPE_URL: &'static str = "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward";
+}
+
+impl ProtoMessage for MsgFundCommunityPool {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.messages(1, &self.amount);
+    buf.string(2, &self.depositor);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgFundCommunityPool {
+      amount: vec![],
+      depositor: String::new(),
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.amount.push(reader.message()?),
+        2 => msg.depositor = reader.string(field)?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgFundCommunityPool {
+  const TYPE_URL: &'static str = "/cosmos.distribution.v1beta1.MsgFundCommunityPool";
+}
+
+impl ProtoMessage for GenericAuthorization {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.msg);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = String::new();
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg = reader.string(field)?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(GenericAuthorization { msg })
+  }
+}
+
+impl ProtoAny for GenericAuthorization {
+  const TYPE_URL: &'static str = "/cosmos.authz.v1beta1.GenericAuthorization";
+}
+
+impl ProtoMessage for Grant {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.optional_message(1, &self.authorization);
+    buf.optional_message(2, &self.expiration);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut grant = Grant {
+      authorization: None,
+      expiration: None,
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => grant.authorization = Some(reader.message()?),
+        2 => grant.expiration = Some(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(grant)
+  }
+}
+
+impl ProtoMessage for MsgGrant {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.granter);
+    buf.string(2, &self.grantee);
+    buf.message(3, &self.grant);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgGrant {
+      granter: String::new(),
+      grantee: String::new(),
+      grant: Grant {
+        authorization: None,
+        expiration: None,
+      },
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.granter = reader.string(field)?,
+        2 => msg.grantee = reader.string(field)?,
+        3 => msg.grant = reader.message()?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgGrant {
+  const TYPE_URL: &'static str = "/cosmos.authz.v1beta1.MsgGrant";
+}
+
+impl ProtoMessage for MsgExec {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.grantee);
+    buf.messages(2, &self.msgs);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgExec {
+      grantee: String::new(),
+      msgs: vec![],
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.grantee = reader.string(field)?,
+        2 => msg.msgs.push(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgExec {
+  const TYPE_URL: &'static str = "/cosmos.authz.v1beta1.MsgExec";
+}
+
+impl ProtoMessage for MsgRevoke {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.granter);
+    buf.string(2, &self.grantee);
+    buf.string(3, &self.msg_type_url);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgRevoke {
+      granter: String::new(),
+      grantee: String::new(),
+      msg_type_url: String::new(),
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.granter = reader.string(field)?,
+        2 => msg.grantee = reader.string(field)?,
+        3 => msg.msg_type_url = reader.string(field)?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgRevoke {
+  const TYPE_URL: &'static str = "/cosmos.authz.v1beta1.MsgRevoke";
+}
+
+impl ProtoMessage for BasicAllowance {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.messages(1, &self.spend_limit);
+    buf.optional_message(2, &self.expiration);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut allowance = BasicAllowance {
+      spend_limit: vec![],
+      expiration: None,
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => allowance.spend_limit.push(reader.message()?),
+        2 => allowance.expiration = Some(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(allowance)
+  }
+}
+
+impl ProtoAny for BasicAllowance {
+  const TYPE_URL: &'static str = "/cosmos.feegrant.v1beta1.BasicAllowance";
+}
+
+impl ProtoMessage for MsgGrantAllowance {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.granter);
+    buf.string(2, &self.grantee);
+    buf.optional_message(3, &self.allowance);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgGrantAllowance {
+      granter: String::new(),
+      grantee: String::new(),
+      allowance: None,
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.granter = reader.string(field)?,
+        2 => msg.grantee = reader.string(field)?,
+        3 => msg.allowance = Some(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgGrantAllowance {
+  const TYPE_URL: &'static str = "/cosmos.feegrant.v1beta1.MsgGrantAllowance";
+}
+
+impl ProtoMessage for MsgRevokeAllowance {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.string(1, &self.granter);
+    buf.string(2, &self.grantee);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let (granter, grantee) = decode_string_pair(reader)?;
+    Ok(MsgRevokeAllowance { granter, grantee })
+  }
+}
+
+impl ProtoAny for MsgRevokeAllowance {
+  const TYPE_URL: &'static str = "/cosmos.feegrant.v1beta1.MsgRevokeAllowance";
+}
+
+impl ProtoMessage for MsgVote {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.uint64(1, self.proposal_id);
+    buf.string(2, &self.voter);
+    buf.uint64(3, self.option.into());
+    buf.string(4, &self.metadata);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgVote {
+      proposal_id: 0,
+      voter: String::new(),
+      option: VoteOption::Unspecified,
+      metadata: String::new(),
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.proposal_id = reader.varint()?,
+        2 => msg.voter = reader.string(field)?,
+        3 => msg.option = reader.varint()?.try_into()?,
+        4 => msg.metadata = reader.string(field)?,
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgVote {
+  const TYPE_URL: &'static str = "/cosmos.gov.v1.MsgVote";
+}
+
+impl ProtoMessage for MsgDeposit {
+  fn encode_to(&self, buf: &mut ProtoWriter) {
+    buf.uint64(1, self.proposal_id);
+    buf.string(2, &self.depositor);
+    buf.messages(3, &self.amount);
+  }
+
+  fn decode_from(reader: &mut ProtoReader) -> ProtoResult<Self> {
+    let mut msg = MsgDeposit {
+      proposal_id: 0,
+      depositor: String::new(),
+      amount: vec![],
+    };
+    while let Some((field, wire_type)) = reader.next_field()? {
+      match field {
+        1 => msg.proposal_id = reader.varint()?,
+        2 => msg.depositor = reader.string(field)?,
+        3 => msg.amount.push(reader.message()?),
+        _ => reader.skip(wire_type)?,
+      }
+    }
+    Ok(msg)
+  }
+}
+
+impl ProtoAny for MsgDeposit {
+  const TYPE_URL: &'static str = "/cosmos.gov.v1.MsgDeposit";
+}
+
+fn empty_coin() -> Coin {
+  Coin::new(0u128, "")
+}
+
+/// Decode the `(delegator_address, validator_address, amount)` fields shared by delegations.
+fn decode_delegation(reader: &mut ProtoReader) -> ProtoResult<(String, String, Coin)> {
+  let mut delegator = String::new();
+  let mut validator = String::new();
+  let mut amount = empty_coin();
+  while let Some((field, wire_type)) = reader.next_field()? {
+    match field {
+      1 => delegator = reader.string(field)?,
+      2 => validator = reader.string(field)?,
+      3 => amount = reader.message()?,
+      _ => reader.skip(wire_type)?,
+    }
+  }
+  Ok((delegator, validator, amount))
+}
+
+/// Decode a message with string fields 1 and 2.
+fn decode_string_pair(reader: &mut ProtoReader) -> ProtoResult<(String, String)> {
+  let mut first = String::new();
+  let mut second = String::new();
+  while let Some((field, wire_type)) = reader.next_field()? {
+    match field {
+      1 => first = reader.string(field)?,
+      2 => second = reader.string(field)?,
+      _ => reader.skip(wire_type)?,
+    }
+  }
+  Ok((first, second))
+}
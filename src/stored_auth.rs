@@ -0,0 +1,105 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty, Storage};
+use cw_storage_plus::Map;
+
+use crate::{paginate, AuthError, XcosmResult};
+
+/// Page of members, sorted by address.
+#[cw_serde]
+pub struct MembersResponse {
+  pub members: Vec<Addr>,
+}
+
+/// Storage-backed auth handler for large groups.
+///
+/// Unlike [`Authorized`](crate::Authorized), members are stored under their own keys, so each
+/// check is a single storage read regardless of the size of the group.
+pub struct StoredAuthorized {
+  namespace: &'static str,
+}
+
+impl StoredAuthorized {
+  /// Create a new [`StoredAuthorized`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self { namespace }
+  }
+
+  fn members<'a>(&self) -> Map<&'a Addr, Empty> {
+    Map::new(self.namespace)
+  }
+
+  /// Whether `addr` is a member.
+  pub fn contains(&self, storage: &dyn Storage, addr: &Addr) -> bool {
+    self.members().has(storage, addr)
+  }
+
+  /// Add a member.
+  pub fn add(&self, storage: &mut dyn Storage, addr: &Addr) -> XcosmResult {
+    self.members().save(storage, addr, &Empty {})?;
+    Ok(())
+  }
+
+  /// Add each of `addrs` as members.
+  pub fn add_many(&self, storage: &mut dyn Storage, addrs: &[Addr]) -> XcosmResult {
+    for addr in addrs {
+      self.add(storage, addr)?;
+    }
+    Ok(())
+  }
+
+  /// Remove a member.
+  pub fn remove(&self, storage: &mut dyn Storage, addr: &Addr) {
+    self.members().remove(storage, addr);
+  }
+
+  /// Remove each of `addrs` from the members.
+  pub fn remove_many(&self, storage: &mut dyn Storage, addrs: &[Addr]) {
+    for addr in addrs {
+      self.remove(storage, addr);
+    }
+  }
+
+  /// Authorize a single requestor.
+  ///
+  /// Requires requestor to be a member.
+  pub fn authorize(&self, storage: &dyn Storage, requestor: &Addr) -> XcosmResult {
+    match self.contains(storage, requestor) {
+      true => Ok(()),
+      false => Err(AuthError::Unauthorized {}.into()),
+    }
+  }
+
+  /// Authorize any of the requestors.
+  ///
+  /// Requires at least one of `requestors` to be a member.
+  pub fn authorize_any(&self, storage: &dyn Storage, requestors: &[Addr]) -> XcosmResult {
+    match requestors.iter().any(|r| self.contains(storage, r)) {
+      true => Ok(()),
+      false => Err(AuthError::Unauthorized {}.into()),
+    }
+  }
+
+  /// Authorize all of the requestors.
+  ///
+  /// Requires `requestors` to not be empty and all of them to be members.
+  pub fn authorize_all(&self, storage: &dyn Storage, requestors: &[Addr]) -> XcosmResult {
+    match !requestors.is_empty() && requestors.iter().all(|r| self.contains(storage, r)) {
+      true => Ok(()),
+      false => Err(AuthError::Unauthorized {}.into()),
+    }
+  }
+
+  /// Query a page of members sorted by address, starting after `start_after`.
+  pub fn list(
+    &self,
+    storage: &dyn Storage,
+    start_after: Option<&Addr>,
+    limit: Option<u32>,
+  ) -> XcosmResult<MembersResponse> {
+    let members = paginate(storage, &self.members(), start_after, limit)?
+      .into_iter()
+      .map(|(member, _)| member)
+      .collect();
+    Ok(MembersResponse { members })
+  }
+}
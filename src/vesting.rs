@@ -0,0 +1,378 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, BlockInfo, CosmosMsg, Decimal, Storage, Uint128};
+use cw_storage_plus::Map;
+
+use crate::{CoinSet, MathError, Rounding, TryMinus, TryMulRatio, TryPlusMut, XcosmResult};
+
+#[derive(Debug, thiserror::Error, miette::Diagnostic)]
+pub enum VestingError {
+  #[error("Invalid vesting schedule: {reason}")]
+  InvalidSchedule { reason: String },
+
+  #[error("Vesting schedule has already been revoked")]
+  Revoked {},
+
+  #[error("No vested funds to release")]
+  NothingVested {},
+
+  #[error("No vesting schedule for {address}")]
+  NotFound { address: String },
+
+  #[error("Vesting schedule already exists for {address}")]
+  AlreadyExists { address: String },
+}
+
+/// Clock which vesting moments are measured in.
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum VestingClock {
+  /// Moments are block times in seconds.
+  #[default]
+  Time,
+  /// Moments are block heights.
+  Height,
+}
+
+impl VestingClock {
+  /// Get the current moment of `block` on this clock.
+  pub fn moment(&self, block: &BlockInfo) -> u64 {
+    match self {
+      VestingClock::Time => block.time.seconds(),
+      VestingClock::Height => block.height,
+    }
+  }
+}
+
+/// Point on a [`VestingCurve::Piecewise`] curve.
+#[cw_serde]
+#[derive(Copy)]
+pub struct VestingPoint {
+  /// Moment of the point.
+  pub at: u64,
+  /// Fraction of funds vested at the moment.
+  pub vested: Decimal,
+}
+
+/// Shape of a [`VestingSchedule`] over time.
+#[cw_serde]
+pub enum VestingCurve {
+  /// Vest linearly from `start` to `end`.
+  Linear { start: u64, end: u64 },
+  /// Vest linearly from `start` to `end`, releasing nothing until `cliff`.
+  CliffLinear { start: u64, cliff: u64, end: u64 },
+  /// Vest in `steps` equal steps, one every `period` after `start`.
+  Periodic { start: u64, period: u64, steps: u64 },
+  /// Vest linearly between each of the points, which must be in ascending order and end
+  /// fully vested.
+  Piecewise { points: Vec<VestingPoint> },
+}
+
+impl VestingCurve {
+  /// Validate the curve.
+  pub fn validate(&self) -> XcosmResult {
+    let invalid = |reason: &str| {
+      Err(
+        VestingError::InvalidSchedule {
+          reason: reason.to_string(),
+        }
+        .into(),
+      )
+    };
+    match self {
+      VestingCurve::Linear { start, end } if end <= start => invalid("end must be after start"),
+      VestingCurve::CliffLinear { start, end, .. } if end <= start => {
+        invalid("end must be after start")
+      }
+      VestingCurve::CliffLinear { start, cliff, end } if cliff < start || cliff > end => {
+        invalid("cliff must be between start and end")
+      }
+      VestingCurve::Periodic { period, .. } if *period == 0 => invalid("period must be non-zero"),
+      VestingCurve::Periodic { steps, .. } if *steps == 0 => invalid("steps must be non-zero"),
+      VestingCurve::Piecewise { points } => {
+        match points.last() {
+          Some(last) if last.vested == Decimal::one() => {}
+          _ => return invalid("points must end fully vested"),
+        }
+        if points
+          .windows(2)
+          .any(|w| w[1].at <= w[0].at || w[1].vested < w[0].vested)
+        {
+          return invalid("points must be ascending");
+        }
+        Ok(())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Get the vested fraction at `moment` as a `(numerator, denominator)` ratio.
+  pub fn vested_ratio(&self, moment: u64) -> XcosmResult<(Uint128, Uint128)> {
+    let linear = |start: u64, end: u64| match moment {
+      m if m <= start => (Uint128::zero(), Uint128::one()),
+      m if m >= end => (Uint128::one(), Uint128::one()),
+      m => (Uint128::from(m - start), Uint128::from(end - start)),
+    };
+    Ok(match self {
+      VestingCurve::Linear { start, end } => linear(*start, *end),
+      VestingCurve::CliffLinear { cliff, .. } if moment < *cliff => {
+        (Uint128::zero(), Uint128::one())
+      }
+      VestingCurve::CliffLinear { start, end, .. } => linear(*start, *end),
+      VestingCurve::Periodic {
+        start,
+        period,
+        steps,
+      } => {
+        let done = moment.saturating_sub(*start) / period;
+        (Uint128::from(done.min(*steps)), Uint128::from(*steps))
+      }
+      VestingCurve::Piecewise { points } => {
+        let fractional = Uint128::from(10u128.pow(Decimal::DECIMAL_PLACES));
+        let after = points.iter().position(|point| point.at > moment);
+        match after {
+          Some(0) => (Uint128::zero(), Uint128::one()),
+          None => (Uint128::one(), Uint128::one()),
+          Some(i) => {
+            let (from, to) = (points[i - 1], points[i]);
+            let (elapsed, duration) = (
+              Uint128::from(moment - from.at),
+              Uint128::from(to.at - from.at),
+            );
+            let numerator = from
+              .vested
+              .atomics()
+              .checked_mul(duration)
+              .and_then(|base| {
+                (to.vested - from.vested)
+                  .atomics()
+                  .checked_mul(elapsed)
+                  .and_then(|step| base.checked_add(step))
+              })
+              .map_err(MathError::from)?;
+            let denominator = fractional.checked_mul(duration).map_err(MathError::from)?;
+            (numerator, denominator)
+          }
+        }
+      }
+    })
+  }
+}
+
+/// Schedule vesting a [`CoinSet`] over time.
+#[cw_serde]
+pub struct VestingSchedule {
+  /// Total funds vesting, including the unvested funds returned on revocation.
+  pub total: CoinSet,
+  /// Funds already released.
+  pub released: CoinSet,
+  /// Clock the curve is measured in.
+  pub clock: VestingClock,
+  /// Shape of the schedule.
+  pub curve: VestingCurve,
+  /// Moment the schedule was revoked, after which nothing more vests.
+  pub revoked_at: Option<u64>,
+}
+
+impl VestingSchedule {
+  /// Create a new [`VestingSchedule`].
+  ///
+  /// Requires the curve to be valid.
+  pub fn new(total: CoinSet, clock: VestingClock, curve: VestingCurve) -> XcosmResult<Self> {
+    curve.validate()?;
+    Ok(Self {
+      total,
+      released: CoinSet::default(),
+      clock,
+      curve,
+      revoked_at: None,
+    })
+  }
+
+  /// Get the funds vested at `moment`, a block time in seconds or block height depending on
+  /// the schedule's clock.
+  pub fn vested_at(&self, moment: u64) -> XcosmResult<CoinSet> {
+    let moment = self
+      .revoked_at
+      .map_or(moment, |revoked| moment.min(revoked));
+    let (numerator, denominator) = self.curve.vested_ratio(moment)?;
+    // round down so funds are never released ahead of schedule
+    Ok(
+      self
+        .total
+        .try_mul_ratio(numerator, denominator, Rounding::Down)?,
+    )
+  }
+
+  /// Get the funds vested at `block`.
+  pub fn vested(&self, block: &BlockInfo) -> XcosmResult<CoinSet> {
+    self.vested_at(self.clock.moment(block))
+  }
+
+  /// Get the funds not yet vested at `block`. Nothing is unvested once the schedule is
+  /// revoked, as the unvested funds were returned by [`VestingSchedule::revoke`].
+  pub fn unvested(&self, block: &BlockInfo) -> XcosmResult<CoinSet> {
+    if self.revoked_at.is_some() {
+      return Ok(CoinSet::default());
+    }
+    let mut unvested = self.total.try_minus(&self.vested(block)?)?;
+    unvested.retain(|_, amount| !amount.is_zero());
+    Ok(unvested)
+  }
+
+  /// Get the vested funds not yet released at `block`.
+  pub fn releasable(&self, block: &BlockInfo) -> XcosmResult<CoinSet> {
+    let mut releasable = self.vested(block)?.try_minus(&self.released)?;
+    releasable.retain(|_, amount| !amount.is_zero());
+    Ok(releasable)
+  }
+
+  /// Release the vested funds not yet released at `block`.
+  ///
+  /// Requires some funds to be releasable.
+  pub fn release(&mut self, block: &BlockInfo) -> XcosmResult<CoinSet> {
+    let releasable = self.releasable(block)?;
+    if releasable.is_empty() {
+      return Err(VestingError::NothingVested {}.into());
+    }
+    self.released.try_plus_mut(&releasable)?;
+    Ok(releasable)
+  }
+
+  /// Revoke the schedule at `block`, returning the unvested funds.
+  ///
+  /// Funds vested before revocation remain releasable. Requires the schedule to not already
+  /// be revoked.
+  pub fn revoke(&mut self, block: &BlockInfo) -> XcosmResult<CoinSet> {
+    if self.revoked_at.is_some() {
+      return Err(VestingError::Revoked {}.into());
+    }
+    let unvested = self.unvested(block)?;
+    self.revoked_at = Some(self.clock.moment(block));
+    Ok(unvested)
+  }
+}
+
+/// Vesting execute messages to embed in a contract's execute message.
+#[cw_serde]
+pub enum VestingExecuteMsg {
+  /// Release the sender's vested funds.
+  Release {},
+  /// Revoke a beneficiary's schedule, returning the unvested funds.
+  Revoke { beneficiary: String },
+}
+
+/// Vesting query messages to embed in a contract's query message.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum VestingQueryMsg {
+  /// Get a beneficiary's schedule and vesting progress.
+  #[returns(VestingResponse)]
+  Vesting { beneficiary: String },
+}
+
+/// Schedule and vesting progress of a beneficiary.
+#[cw_serde]
+pub struct VestingResponse {
+  pub beneficiary: Addr,
+  pub schedule: VestingSchedule,
+  pub vested: CoinSet,
+  pub releasable: CoinSet,
+}
+
+/// Storage-backed vesting schedules, one per beneficiary.
+pub struct VestingSchedules {
+  namespace: &'static str,
+}
+
+impl VestingSchedules {
+  /// Create new [`VestingSchedules`] stored under `namespace`.
+  pub const fn new(namespace: &'static str) -> Self {
+    Self { namespace }
+  }
+
+  fn schedules<'a>(&self) -> Map<&'a Addr, VestingSchedule> {
+    Map::new(self.namespace)
+  }
+
+  /// Create the schedule for `beneficiary`.
+  ///
+  /// Requires the beneficiary to not already have a schedule.
+  pub fn create(
+    &self,
+    storage: &mut dyn Storage,
+    beneficiary: &Addr,
+    schedule: &VestingSchedule,
+  ) -> XcosmResult {
+    if self.schedules().has(storage, beneficiary) {
+      return Err(
+        VestingError::AlreadyExists {
+          address: beneficiary.to_string(),
+        }
+        .into(),
+      );
+    }
+    schedule.curve.validate()?;
+    self.schedules().save(storage, beneficiary, schedule)?;
+    Ok(())
+  }
+
+  /// Load the schedule for `beneficiary`.
+  pub fn load(&self, storage: &dyn Storage, beneficiary: &Addr) -> XcosmResult<VestingSchedule> {
+    self
+      .schedules()
+      .may_load(storage, beneficiary)?
+      .ok_or_else(|| {
+        VestingError::NotFound {
+          address: beneficiary.to_string(),
+        }
+        .into()
+      })
+  }
+
+  /// Release the beneficiary's vested funds, returning the bank message paying them out.
+  pub fn release(
+    &self,
+    storage: &mut dyn Storage,
+    beneficiary: &Addr,
+    block: &BlockInfo,
+  ) -> XcosmResult<CosmosMsg> {
+    let mut schedule = self.load(storage, beneficiary)?;
+    let released = schedule.release(block)?;
+    self.schedules().save(storage, beneficiary, &schedule)?;
+    released.send(beneficiary)
+  }
+
+  /// Revoke the beneficiary's schedule, returning the bank message refunding the unvested
+  /// funds to `refund_to`, if any.
+  pub fn revoke(
+    &self,
+    storage: &mut dyn Storage,
+    beneficiary: &Addr,
+    block: &BlockInfo,
+    refund_to: &Addr,
+  ) -> XcosmResult<Option<CosmosMsg>> {
+    let mut schedule = self.load(storage, beneficiary)?;
+    let unvested = schedule.revoke(block)?;
+    self.schedules().save(storage, beneficiary, &schedule)?;
+    match unvested.is_empty() {
+      true => Ok(None),
+      false => unvested.send(refund_to).map(Some),
+    }
+  }
+
+  /// Query the beneficiary's schedule and vesting progress at `block`.
+  pub fn query(
+    &self,
+    storage: &dyn Storage,
+    beneficiary: &Addr,
+    block: &BlockInfo,
+  ) -> XcosmResult<VestingResponse> {
+    let schedule = self.load(storage, beneficiary)?;
+    Ok(VestingResponse {
+      beneficiary: beneficiary.clone(),
+      vested: schedule.vested(block)?,
+      releasable: schedule.releasable(block)?,
+      schedule,
+    })
+  }
+}
@@ -0,0 +1,259 @@
+use cosmwasm_std::{testing::mock_env, BlockInfo, Decimal, Timestamp};
+use xcosm::*;
+
+fn s(value: &str) -> String {
+  value.to_string()
+}
+
+fn block(height: u64, seconds: u64) -> BlockInfo {
+  BlockInfo {
+    height,
+    time: Timestamp::from_seconds(seconds),
+    ..mock_env().block
+  }
+}
+
+#[test]
+fn expiring_height_boundaries() {
+  let member = Expiring::delayed(s("a"), Expiry::AtHeight(10), Expiry::AtHeight(20));
+  assert!(!member.is_active(&block(9, 0)));
+  assert!(member.is_active(&block(10, 0)));
+  assert!(member.is_active(&block(19, 0)));
+  assert!(!member.is_active(&block(20, 0)));
+  assert!(!member.is_expired(&block(19, 0)));
+  assert!(member.is_expired(&block(20, 0)));
+  assert!(Expiring::new(s("a"), Expiry::Never).is_active(&block(0, 0)));
+}
+
+#[test]
+fn expiring_time_boundaries() {
+  let member = Expiring::delayed(
+    s("a"),
+    Expiry::AtTime(Timestamp::from_seconds(100)),
+    Expiry::AtTime(Timestamp::from_seconds(200)),
+  );
+  assert!(!member.is_active(&block(1000, 99)));
+  assert!(member.is_active(&block(0, 100)));
+  assert!(member.is_active(&block(0, 199)));
+  assert!(!member.is_active(&block(0, 200)));
+  assert!(member.is_expired(&block(0, 200)));
+}
+
+/// "now" expires at height 10, "later" is active from height 5 until 20 and "timed" is active
+/// from time 100 until 200.
+fn group() -> Authorized<Expiring<String>> {
+  Authorized::Many(vec![
+    Expiring::new(s("now"), Expiry::AtHeight(10)),
+    Expiring::delayed(s("later"), Expiry::AtHeight(5), Expiry::AtHeight(20)),
+    Expiring::delayed(
+      s("timed"),
+      Expiry::AtTime(Timestamp::from_seconds(100)),
+      Expiry::AtTime(Timestamp::from_seconds(200)),
+    ),
+  ])
+}
+
+#[test]
+fn active_at() {
+  let group = group();
+  assert_eq!(group.active_at(&block(4, 99)), Authorized::One(s("now")));
+  assert_eq!(
+    group.active_at(&block(5, 100)),
+    Authorized::Many(vec![s("now"), s("later"), s("timed")])
+  );
+  assert_eq!(
+    group.active_at(&block(10, 199)),
+    Authorized::Many(vec![s("later"), s("timed")])
+  );
+  assert_eq!(group.active_at(&block(20, 200)), Authorized::None);
+  assert_eq!(
+    Authorized::One(Expiring::new(s("a"), Expiry::AtHeight(1))).active_at(&block(1, 0)),
+    Authorized::None
+  );
+  assert_eq!(
+    Authorized::<Expiring<String>>::Any.active_at(&block(1, 0)),
+    Authorized::Any
+  );
+}
+
+#[test]
+fn prune_expired_keeps_pending_members() {
+  let mut group = group();
+  group.prune_expired(&block(0, 0));
+  assert_eq!(group, self::group());
+  group.prune_expired(&block(10, 0));
+  let Authorized::Many(members) = &group else {
+    panic!("expected many members, got {group:?}");
+  };
+  assert_eq!(
+    members.iter().map(|m| m.value.as_str()).collect::<Vec<_>>(),
+    ["later", "timed"]
+  );
+  group.prune_expired(&block(20, 0));
+  assert!(matches!(&group, Authorized::One(member) if member.value == "timed"));
+  group.prune_expired(&block(20, 200));
+  assert_eq!(group, Authorized::None);
+}
+
+#[test]
+fn authorize_at() {
+  let group = group();
+  let unauthorized =
+    |res: XcosmResult| matches!(res, Err(XcosmError::Auth(AuthError::Unauthorized {})));
+  assert!(unauthorized(group.authorize_at(&s("later"), &block(4, 0))));
+  group.authorize_at(&s("later"), &block(5, 0)).unwrap();
+  assert!(unauthorized(group.authorize_at(&s("now"), &block(10, 0))));
+  assert!(unauthorized(group.authorize_at(&s("timed"), &block(0, 99))));
+  group.authorize_at(&s("timed"), &block(0, 100)).unwrap();
+  assert!(unauthorized(
+    group.authorize_at(&s("timed"), &block(0, 200))
+  ));
+
+  group
+    .authorize_any_at(&[s("later"), s("now")], &block(4, 0))
+    .unwrap();
+  assert!(group
+    .authorize_any_at(&[s("later"), s("timed")], &block(4, 99))
+    .is_err());
+  group
+    .authorize_all_at(&[s("later"), s("timed")], &block(5, 100))
+    .unwrap();
+  assert!(group
+    .authorize_all_at(&[s("now"), s("later")], &block(10, 100))
+    .is_err());
+  group
+    .authorize_at_least_at(&[s("now"), s("later"), s("timed")], 2, &block(5, 99))
+    .unwrap();
+  assert!(group
+    .authorize_at_least_at(&[s("now"), s("later"), s("timed")], 2, &block(10, 99))
+    .is_err());
+}
+
+fn weighted(
+  members: &[(&str, u64)],
+  threshold: Threshold,
+) -> XcosmResult<WeightedAuthorized<String>> {
+  WeightedAuthorized::new(
+    members
+      .iter()
+      .map(|(member, weight)| Weighted {
+        member: s(member),
+        weight: *weight,
+      })
+      .collect(),
+    threshold,
+  )
+}
+
+fn invalid_weights<T: std::fmt::Debug>(res: XcosmResult<T>) -> bool {
+  matches!(res, Err(XcosmError::Auth(AuthError::InvalidWeights { .. })))
+}
+
+fn insufficient(res: XcosmResult, achieved: u64, required: u64) -> bool {
+  matches!(
+    res,
+    Err(XcosmError::Auth(AuthError::InsufficientWeight { achieved: a, required: r }))
+      if a == achieved && r == required
+  )
+}
+
+#[test]
+fn weighted_validation() {
+  let absolute = Threshold::Absolute { weight: 1 };
+  weighted(&[("a", 1), ("b", 2)], absolute.clone()).unwrap();
+  assert!(invalid_weights(weighted(
+    &[("a", 1), ("b", 0)],
+    absolute.clone()
+  )));
+  assert!(invalid_weights(weighted(
+    &[("a", 1), ("a", 2)],
+    absolute.clone()
+  )));
+  assert!(invalid_weights(weighted(&[], absolute)));
+  assert!(invalid_weights(weighted(
+    &[("a", 1)],
+    Threshold::Absolute { weight: 2 }
+  )));
+  assert!(invalid_weights(weighted(
+    &[("a", 1)],
+    Threshold::Percentage {
+      percentage: Decimal::percent(101)
+    }
+  )));
+}
+
+#[test]
+fn weighted_counts_duplicate_requestors_once() {
+  let group = weighted(&[("a", 3), ("b", 2)], Threshold::Absolute { weight: 4 }).unwrap();
+  assert_eq!(group.weight_of(&[s("a"), s("a"), s("z")]), 3);
+  assert!(insufficient(
+    group.authorize_weighted(&[s("a"), s("a")]),
+    3,
+    4
+  ));
+  group.authorize_weighted(&[s("a"), s("b")]).unwrap();
+}
+
+#[test]
+fn weighted_percentage_rounds_up() {
+  // 50% of 5 requires 3
+  let group = weighted(
+    &[("a", 2), ("b", 2), ("c", 1)],
+    Threshold::Percentage {
+      percentage: Decimal::percent(50),
+    },
+  )
+  .unwrap();
+  assert!(insufficient(group.authorize_weighted(&[s("a")]), 2, 3));
+  group.authorize_weighted(&[s("a"), s("c")]).unwrap();
+}
+
+#[test]
+fn weighted_quorum() {
+  // quorum of 60% of 10 requires 6 participating, then half of those approving
+  let group = weighted(
+    &[("a", 4), ("b", 3), ("c", 3)],
+    Threshold::Quorum {
+      quorum: Decimal::percent(60),
+      threshold: Decimal::percent(50),
+    },
+  )
+  .unwrap();
+  assert!(matches!(
+    group.authorize_votes(&[s("a")], &[]),
+    Err(XcosmError::Auth(AuthError::QuorumNotReached {
+      participating: 4,
+      required: 6
+    }))
+  ));
+  group.authorize_votes(&[s("a")], &[s("b")]).unwrap();
+  assert!(insufficient(
+    group.authorize_votes(&[s("b")], &[s("a")]),
+    3,
+    4
+  ));
+  // a member both approving and rejecting participates once, as an approval
+  assert!(matches!(
+    group.authorize_votes(&[s("a")], &[s("a")]),
+    Err(XcosmError::Auth(AuthError::QuorumNotReached {
+      participating: 4,
+      ..
+    }))
+  ));
+}
+
+#[test]
+fn authorize_at_least_counts_distinct_members() {
+  let one = Authorized::One(s("a"));
+  one.authorize_at_least(&[s("a")], 1).unwrap();
+  assert!(one.authorize_at_least(&[s("a"), s("a")], 2).is_err());
+  let many = Authorized::Many(vec![s("a"), s("b"), s("c")]);
+  assert!(many.authorize_at_least(&[s("a"), s("a")], 2).is_err());
+  many.authorize_at_least(&[s("a"), s("c")], 2).unwrap();
+  Authorized::<String>::None
+    .authorize_at_least(&[], 0)
+    .unwrap();
+  assert!(Authorized::<String>::None
+    .authorize_at_least(&[s("a")], 1)
+    .is_err());
+}
@@ -0,0 +1,252 @@
+use cosmwasm_std::{testing::message_info, Addr, Coin, Coins, MessageInfo};
+use xcosm::*;
+
+fn c(amount: u128, denom: &str) -> Coin {
+  Coin::new(amount, denom)
+}
+
+fn info(funds: &[Coin]) -> MessageInfo {
+  message_info(&Addr::unchecked("sender"), funds)
+}
+
+/// Messages of each failure reported by `err`, unpacking a [`CoinError::Mismatch`].
+fn failures<T: std::fmt::Debug>(res: XcosmResult<T>) -> Vec<String> {
+  match res.unwrap_err() {
+    XcosmError::Coin(CoinError::Mismatch { failures }) => {
+      failures.iter().map(|failure| failure.to_string()).collect()
+    }
+    err => vec![err.to_string()],
+  }
+}
+
+#[test]
+fn expect_funds() {
+  let info = info(&[c(10, "a"), c(3, "b")]);
+  info.expect_funds(vec![c(5, "a")]).unwrap();
+  info.expect_funds(vec![c(10, "a"), c(3, "b")]).unwrap();
+  assert_eq!(
+    failures(info.expect_funds(vec![c(11, "a"), c(1, "z")])),
+    ["Insufficient a: expected 11, received 10", "Missing 1z"]
+  );
+  // repeated denoms are all required
+  assert_eq!(
+    failures(info.expect_funds(vec![c(5, "a"), c(6, "a")])),
+    ["Insufficient a: expected 11, received 10"]
+  );
+}
+
+#[test]
+fn expect_funds_exact() {
+  let info = info(&[c(10, "a"), c(3, "b")]);
+  info
+    .expect_funds_exact(vec![c(10, "a"), c(3, "b")])
+    .unwrap();
+  info
+    .expect_funds_exact(vec![c(5, "a"), c(5, "a"), c(3, "b")])
+    .unwrap();
+  assert_eq!(
+    failures(info.expect_funds_exact(vec![c(10, "a")])),
+    ["Unexpected 3b"]
+  );
+  assert_eq!(
+    failures(info.expect_funds_exact(vec![c(9, "a"), c(4, "b")])),
+    [
+      "Insufficient b: expected 4, received 3",
+      "Excess a: expected at most 9, received 10"
+    ]
+  );
+}
+
+#[test]
+fn expect_funds_denoms() {
+  let info = info(&[c(10, "a"), c(3, "b")]);
+  info.expect_funds_denoms(["a", "b"]).unwrap();
+  assert_eq!(
+    failures(info.expect_funds_denoms(["a", "c"])),
+    ["Missing c", "Unexpected 3b"]
+  );
+  assert_eq!(failures(info.expect_funds_denoms(["a"])), ["Unexpected 3b"]);
+  assert!(matches!(
+    info.expect_funds_denoms(["a", "b", "c"]),
+    Err(XcosmError::Coin(CoinError::MissingDenom { denom })) if denom == "c"
+  ));
+}
+
+#[test]
+fn expect_funds_any_of() {
+  let info = info(&[c(10, "a"), c(3, "b")]);
+  assert_eq!(
+    info
+      .expect_funds_any_of(vec![c(100, "a"), c(2, "b")])
+      .unwrap(),
+    c(2, "b")
+  );
+  assert_eq!(
+    info.expect_funds_any_of(vec![c(0, "z")]).unwrap(),
+    c(0, "z")
+  );
+  assert_eq!(
+    failures(info.expect_funds_any_of(vec![c(100, "a"), c(5, "z")])),
+    ["Insufficient a: expected 100, received 10", "Missing 5z"]
+  );
+  assert!(matches!(
+    info.expect_funds_any_of(vec![c(20, "a")]),
+    Err(XcosmError::Coin(CoinError::Insufficient { .. }))
+  ));
+  assert!(matches!(
+    info.expect_funds_any_of(vec![]),
+    Err(XcosmError::Coin(CoinError::Empty {}))
+  ));
+}
+
+#[test]
+fn expect_funds_any_of_reports_each_alternative() {
+  let info = info(&[c(3, "u")]);
+  assert_eq!(
+    failures(info.expect_funds_any_of(vec![c(10, "u"), c(5, "u")])),
+    [
+      "Insufficient u: expected 10, received 3",
+      "Insufficient u: expected 5, received 3"
+    ]
+  );
+}
+
+#[test]
+fn expect_funds_at_most() {
+  let info = info(&[c(10, "a"), c(3, "b")]);
+  info
+    .expect_funds_at_most(vec![c(10, "a"), c(5, "b"), c(1, "z")])
+    .unwrap();
+  info
+    .expect_funds_at_most(vec![c(5, "a"), c(5, "a"), c(3, "b")])
+    .unwrap();
+  assert_eq!(
+    failures(info.expect_funds_at_most(vec![c(9, "a")])),
+    ["Excess a: expected at most 9, received 10", "Unexpected 3b"]
+  );
+}
+
+#[test]
+fn expect_no_funds() {
+  let none = info(&[]);
+  none.expect_no_funds().unwrap();
+  none.expect_funds(vec![]).unwrap();
+  none.expect_funds_at_most(vec![]).unwrap();
+  assert!(matches!(
+    info(&[c(1, "a")]).expect_no_funds(),
+    Err(XcosmError::Coin(CoinError::NotEmpty {}))
+  ));
+}
+
+#[test]
+fn expect_coin() {
+  let funds = info(&[c(10, "a"), c(3, "b")]).fund_set().unwrap();
+  assert_eq!(funds.expect_coin(&c(1, "a")).unwrap().u128(), 10);
+  assert_eq!(
+    failures(funds.expect_coin(&c(11, "a"))),
+    ["Insufficient a: expected 11, received 10"]
+  );
+  assert_eq!(
+    failures(funds.expect_coin_exact(&c(10, "a"))),
+    ["Unexpected 3b"]
+  );
+}
+
+#[test]
+fn duplicate_denoms() {
+  assert_eq!(
+    try_into_coins(vec![c(2, "b"), c(1, "a")])
+      .unwrap()
+      .into_vec(),
+    [c(1, "a"), c(2, "b")]
+  );
+  assert!(matches!(
+    try_into_coins(vec![c(1, "a"), c(2, "b"), c(3, "a")]),
+    Err(XcosmError::Coin(CoinError::DuplicateDenom { denom })) if denom == "a"
+  ));
+  assert_eq!(
+    try_into_coins(vec![c(1, "a"), c(3, "a")])
+      .unwrap_err()
+      .to_string(),
+    "Duplicate denom in coins: \"a\""
+  );
+  let convert = |coins: Vec<Coin>| -> Result<Coins, CoinError> { Ok(coins.try_into()?) };
+  let err = convert(vec![c(1, "a"), c(3, "a")]).unwrap_err();
+  assert!(matches!(err, CoinError::DuplicateDenomUnknown {}));
+  assert_eq!(err.to_string(), "Duplicate denom in coins");
+}
+
+/// Coin set holding exactly `amounts`, zero amounts included.
+fn set(amounts: &[(&str, u128)]) -> CoinSet {
+  CoinSet::new(
+    amounts
+      .iter()
+      .map(|(denom, amount)| (denom.to_string(), (*amount).into()))
+      .collect(),
+  )
+}
+
+#[test]
+fn superset_and_subset() {
+  let big = set(&[("a", 10), ("b", 5)]);
+  let small = set(&[("a", 3)]);
+  assert!(big.is_superset_of(&small));
+  assert!(small.is_subset_of(&big));
+  assert!(!small.is_superset_of(&big));
+  assert!(!big.is_subset_of(&small));
+  assert!(big.is_superset_of(&big) && big.is_subset_of(&big));
+  assert!(big.is_superset_of(&CoinSet::default()));
+  // zero amounts are treated as absent
+  assert!(small.is_superset_of(&set(&[("a", 3), ("z", 0)])));
+}
+
+#[test]
+fn partial_order() {
+  use std::cmp::Ordering;
+
+  let a = set(&[("a", 10), ("b", 5)]);
+  assert_eq!(a.partial_cmp(&set(&[("a", 3)])), Some(Ordering::Greater));
+  assert_eq!(set(&[("a", 3)]).partial_cmp(&a), Some(Ordering::Less));
+  assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+  let incomparable = set(&[("a", 11)]);
+  assert_eq!(a.partial_cmp(&incomparable), None);
+  assert_eq!(incomparable.partial_cmp(&a), None);
+  assert!(a >= a.clone() && a <= a.clone() && a != incomparable);
+}
+
+#[test]
+fn zero_amounts_equal_absent() {
+  assert_eq!(set(&[("a", 1), ("z", 0)]), set(&[("a", 1)]));
+  assert_eq!(set(&[("z", 0)]), CoinSet::default());
+  assert_ne!(set(&[("a", 1)]), set(&[("a", 2)]));
+  assert_eq!(
+    set(&[("a", 1), ("z", 0)]).partial_cmp(&set(&[("a", 1), ("y", 0)])),
+    Some(std::cmp::Ordering::Equal)
+  );
+}
+
+#[test]
+fn intersection_and_union() {
+  let x = set(&[("a", 10), ("b", 5), ("z", 0)]);
+  let y = set(&[("a", 3), ("c", 7)]);
+  let both = x.intersection(&y);
+  assert_eq!(both, set(&[("a", 3)]));
+  assert!(!both.contains_key("b") && !both.contains_key("z"));
+  let either = x.union_max(&y);
+  assert_eq!(either, set(&[("a", 10), ("b", 5), ("c", 7)]));
+  assert!(!either.contains_key("z"));
+  assert_eq!(y.intersection(&x), both);
+  assert_eq!(y.union_max(&x), either);
+}
+
+#[test]
+fn difference_reports_surplus_and_shortfall() {
+  let held = set(&[("a", 10), ("b", 5), ("z", 0)]);
+  let wanted = set(&[("a", 4), ("b", 8), ("c", 2), ("y", 0)]);
+  let (surplus, shortfall) = held.difference(&wanted);
+  assert_eq!(surplus, set(&[("a", 6)]));
+  assert_eq!(shortfall, set(&[("b", 3), ("c", 2)]));
+  assert!(!surplus.contains_key("z") && !shortfall.contains_key("y"));
+  let (surplus, shortfall) = held.difference(&held);
+  assert!(surplus.is_empty() && shortfall.is_empty());
+}
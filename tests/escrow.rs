@@ -0,0 +1,223 @@
+use cosmwasm_std::{
+  coin, coins,
+  testing::{message_info, mock_env, MockStorage},
+  Addr, BankMsg, BlockInfo, Coin, CosmosMsg,
+};
+use xcosm::*;
+
+const ESCROWS: Escrows = Escrows::new("escrows");
+
+#[test]
+fn deposit_rejects_unusable_terms() {
+  let mut storage = MockStorage::new();
+  let block = mock_env().block;
+  let info = message_info(&Addr::unchecked("depositor"), &coins(100, "u"));
+  let beneficiary = Addr::unchecked("beneficiary");
+  let arbiters = Authorized::One(Addr::unchecked("arbiter"));
+  let deposit = |storage: &mut MockStorage, id: &str, arbiters: Authorized, expiry: Expiry| {
+    ESCROWS.deposit(
+      storage,
+      &info,
+      id,
+      beneficiary.clone(),
+      arbiters,
+      expiry,
+      &block,
+    )
+  };
+
+  let err = deposit(
+    &mut storage,
+    "expired",
+    arbiters.clone(),
+    Expiry::AtHeight(block.height),
+  )
+  .unwrap_err();
+  assert!(matches!(
+    err,
+    XcosmError::Escrow(EscrowError::Expired { .. })
+  ));
+  let err = deposit(&mut storage, "locked", Authorized::None, Expiry::Never).unwrap_err();
+  assert!(matches!(
+    err,
+    XcosmError::Escrow(EscrowError::Unreleasable { .. })
+  ));
+  let err = deposit(
+    &mut storage,
+    "locked",
+    Authorized::Many(vec![]),
+    Expiry::Never,
+  )
+  .unwrap_err();
+  assert!(matches!(
+    err,
+    XcosmError::Escrow(EscrowError::Unreleasable { .. })
+  ));
+  assert!(ESCROWS.load(&storage, "expired").is_err());
+  assert!(ESCROWS.load(&storage, "locked").is_err());
+
+  deposit(&mut storage, "arbitrated", arbiters, Expiry::Never).unwrap();
+  deposit(
+    &mut storage,
+    "timed",
+    Authorized::None,
+    Expiry::AtHeight(block.height + 1),
+  )
+  .unwrap();
+  assert_eq!(ESCROWS.list(&storage, None, None).unwrap().escrows.len(), 2);
+}
+
+fn addr(name: &str) -> Addr {
+  Addr::unchecked(name)
+}
+
+fn send(to: &str, amount: Vec<Coin>) -> CosmosMsg {
+  CosmosMsg::Bank(BankMsg::Send {
+    to_address: to.to_string(),
+    amount,
+  })
+}
+
+fn is_unauthorized<T: std::fmt::Debug>(res: XcosmResult<T>) -> bool {
+  matches!(res, Err(XcosmError::Auth(AuthError::Unauthorized {})))
+}
+
+fn is_expired<T: std::fmt::Debug>(res: XcosmResult<T>) -> bool {
+  matches!(res, Err(XcosmError::Escrow(EscrowError::Expired { .. })))
+}
+
+/// Storage holding escrow "e" of 100u from "depositor", arbitrated by "arbiter" and expiring
+/// 10 blocks after the returned block.
+fn escrow() -> (MockStorage, BlockInfo) {
+  let mut storage = MockStorage::new();
+  let block = mock_env().block;
+  ESCROWS
+    .deposit(
+      &mut storage,
+      &message_info(&addr("depositor"), &coins(100, "u")),
+      "e",
+      addr("beneficiary"),
+      Authorized::One(addr("arbiter")),
+      Expiry::AtHeight(block.height + 10),
+      &block,
+    )
+    .unwrap();
+  (storage, block)
+}
+
+fn expired(block: &BlockInfo) -> BlockInfo {
+  BlockInfo {
+    height: block.height + 10,
+    ..block.clone()
+  }
+}
+
+#[test]
+fn release_pays_beneficiary() {
+  let (mut storage, block) = escrow();
+  assert!(is_unauthorized(ESCROWS.release(
+    &mut storage,
+    &addr("depositor"),
+    "e",
+    &block
+  )));
+  assert_eq!(
+    ESCROWS
+      .release(&mut storage, &addr("arbiter"), "e", &block)
+      .unwrap(),
+    send("beneficiary", coins(100, "u"))
+  );
+  assert!(matches!(
+    ESCROWS.load(&storage, "e"),
+    Err(XcosmError::Escrow(EscrowError::NotFound { .. }))
+  ));
+}
+
+#[test]
+fn release_rejected_once_expired() {
+  let (mut storage, block) = escrow();
+  assert!(is_expired(ESCROWS.release(
+    &mut storage,
+    &addr("arbiter"),
+    "e",
+    &expired(&block)
+  )));
+  ESCROWS.load(&storage, "e").unwrap();
+}
+
+#[test]
+fn refund_by_arbiter_before_expiry() {
+  let (mut storage, block) = escrow();
+  assert!(is_unauthorized(ESCROWS.refund(
+    &mut storage,
+    &addr("anyone"),
+    "e",
+    &block
+  )));
+  assert_eq!(
+    ESCROWS
+      .refund(&mut storage, &addr("arbiter"), "e", &block)
+      .unwrap(),
+    send("depositor", coins(100, "u"))
+  );
+  assert!(ESCROWS.load(&storage, "e").is_err());
+}
+
+#[test]
+fn refund_by_anyone_after_expiry() {
+  let (mut storage, block) = escrow();
+  assert_eq!(
+    ESCROWS
+      .refund(&mut storage, &addr("anyone"), "e", &expired(&block))
+      .unwrap(),
+    send("depositor", coins(100, "u"))
+  );
+  assert!(ESCROWS.load(&storage, "e").is_err());
+}
+
+#[test]
+fn top_up_by_depositor_only() {
+  let (mut storage, block) = escrow();
+  let funds = [coin(5, "u"), coin(7, "v")];
+  assert!(is_unauthorized(ESCROWS.top_up(
+    &mut storage,
+    &message_info(&addr("beneficiary"), &funds),
+    "e",
+    &block
+  )));
+  let escrow = ESCROWS
+    .top_up(
+      &mut storage,
+      &message_info(&addr("depositor"), &funds),
+      "e",
+      &block,
+    )
+    .unwrap();
+  assert_eq!(escrow.funds.amount_of("u").u128(), 105);
+  assert_eq!(
+    ESCROWS
+      .release(&mut storage, &addr("arbiter"), "e", &block)
+      .unwrap(),
+    send("beneficiary", vec![coin(105, "u"), coin(7, "v")])
+  );
+}
+
+#[test]
+fn top_up_rejected_once_expired() {
+  let (mut storage, block) = escrow();
+  assert!(is_expired(ESCROWS.top_up(
+    &mut storage,
+    &message_info(&addr("depositor"), &coins(5, "u")),
+    "e",
+    &expired(&block)
+  )));
+  assert_eq!(
+    ESCROWS
+      .load(&storage, "e")
+      .unwrap()
+      .funds
+      .amount_of("u")
+      .u128(),
+    100
+  );
+}
@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{Addr, Uint128};
+use xcosm::*;
+
+fn coins(amounts: &[(&str, u128)]) -> CoinSet {
+  CoinSet::new(
+    amounts
+      .iter()
+      .map(|(denom, amount)| (denom.to_string(), Uint128::new(*amount)))
+      .collect::<BTreeMap<_, _>>(),
+  )
+}
+
+/// 1% plus a flat 2u, clamped between 5u and 50u.
+fn config() -> FeeConfig {
+  FeeConfig {
+    bps: 100,
+    flat: coins(&[("u", 2)]),
+    min: coins(&[("u", 5)]),
+    max: coins(&[("u", 50)]),
+    ..FeeConfig::default()
+  }
+}
+
+fn fee(config: &FeeConfig, amount: u128) -> u128 {
+  config.fee_amount("u", Uint128::new(amount)).unwrap().u128()
+}
+
+fn fee_invalid(res: XcosmResult) -> bool {
+  matches!(res, Err(XcosmError::Fund(FundError::FeeInvalid { .. })))
+}
+
+#[test]
+fn bps_plus_flat() {
+  let config = config();
+  assert_eq!(fee(&config, 1000), 12);
+  assert_eq!(fee(&config, 1050), 12);
+  // denoms without a flat fee or bounds only pay the bps fee
+  assert_eq!(
+    config.fee_amount("v", Uint128::new(1050)).unwrap().u128(),
+    10
+  );
+}
+
+#[test]
+fn clamped_between_min_and_max() {
+  let config = config();
+  assert_eq!(fee(&config, 0), 5);
+  assert_eq!(fee(&config, 200), 5);
+  assert_eq!(fee(&config, 4800), 50);
+  assert_eq!(fee(&config, 1_000_000), 50);
+}
+
+#[test]
+fn rounding_up() {
+  let config = FeeConfig {
+    rounding: Rounding::Up,
+    ..config()
+  };
+  assert_eq!(fee(&config, 1000), 12);
+  assert_eq!(fee(&config, 1001), 13);
+}
+
+#[test]
+fn take_fee() {
+  let (sender, contract) = (Addr::unchecked("sender"), Addr::unchecked("contract"));
+  let (net, fee, msgs) = config()
+    .take_fee(&sender, &coins(&[("u", 1000), ("v", 1000)]), &contract)
+    .unwrap();
+  assert_eq!(net, coins(&[("u", 988), ("v", 990)]));
+  assert_eq!(fee, coins(&[("u", 12), ("v", 10)]));
+  assert!(msgs.is_empty());
+}
+
+#[test]
+fn take_fee_consumes_small_amounts() {
+  let (sender, contract) = (Addr::unchecked("sender"), Addr::unchecked("contract"));
+  let (net, fee, _) = config()
+    .take_fee(&sender, &coins(&[("u", 5)]), &contract)
+    .unwrap();
+  assert_eq!(net, CoinSet::default());
+  assert_eq!(fee, coins(&[("u", 5)]));
+}
+
+#[test]
+fn exempt_sender_pays_nothing() {
+  let config = FeeConfig {
+    exempt: Authorized::One(Addr::unchecked("exempt")),
+    ..config()
+  };
+  let funds = coins(&[("u", 1000)]);
+  let (net, fee, msgs) = config
+    .take_fee(
+      &Addr::unchecked("exempt"),
+      &funds,
+      &Addr::unchecked("contract"),
+    )
+    .unwrap();
+  assert_eq!(net, funds);
+  assert_eq!(fee, CoinSet::default());
+  assert!(msgs.is_empty());
+}
+
+#[test]
+fn insufficient_funds_for_fee() {
+  let res = config().take_fee(
+    &Addr::unchecked("sender"),
+    &coins(&[("u", 4)]),
+    &Addr::unchecked("contract"),
+  );
+  assert!(matches!(
+    res,
+    Err(XcosmError::Coin(CoinError::Insufficient { denom, expected, received }))
+      if denom == "u" && expected.u128() == 5 && received.u128() == 4
+  ));
+}
+
+#[test]
+fn fees_routed_through_distribution() {
+  let distribution = Distribution::new(BTreeMap::from([
+    (Addr::unchecked("a"), Claim::new(5000)),
+    (Addr::unchecked("b"), Claim::new(5000)),
+  ]));
+  let config = FeeConfig {
+    distribution: Some(distribution.clone()),
+    ..config()
+  };
+  let contract = Addr::unchecked("contract");
+  let (_, fee, msgs) = config
+    .take_fee(
+      &Addr::unchecked("sender"),
+      &coins(&[("u", 1000)]),
+      &contract,
+    )
+    .unwrap();
+  assert_eq!(fee, coins(&[("u", 12)]));
+  assert_eq!(
+    msgs,
+    distribution.distribute_coins(&contract, &fee).unwrap()
+  );
+  assert_eq!(msgs.len(), 1);
+}
+
+#[test]
+fn validate() {
+  config().validate().unwrap();
+  FeeConfig {
+    bps: MAX_BPS,
+    ..FeeConfig::default()
+  }
+  .validate()
+  .unwrap();
+  assert!(fee_invalid(
+    FeeConfig {
+      bps: MAX_BPS + 1,
+      ..FeeConfig::default()
+    }
+    .validate()
+  ));
+  assert!(fee_invalid(
+    FeeConfig {
+      min: coins(&[("u", 51)]),
+      ..config()
+    }
+    .validate()
+  ));
+}
@@ -0,0 +1,622 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{
+  from_json,
+  testing::{message_info, MockApi, MockStorage},
+  to_json_string, Addr, Api, CanonicalAddr, Coin, CosmosMsg, RecoverPubkeyError, StdResult,
+  Storage, Uint128, VerificationError,
+};
+use cw_storage_plus::Item;
+use xcosm::*;
+
+fn distribution(claims: &[(&str, u32)]) -> Distribution {
+  Distribution::new(
+    claims
+      .iter()
+      .map(|(addr, bps)| (Addr::unchecked(*addr), Claim::new(*bps)))
+      .collect(),
+  )
+}
+
+fn coins(amounts: &[(&str, u128)]) -> CoinSet {
+  CoinSet::new(
+    amounts
+      .iter()
+      .map(|(denom, amount)| (denom.to_string(), Uint128::new(*amount)))
+      .collect::<BTreeMap<_, _>>(),
+  )
+}
+
+fn split(claims: &[(&str, u32)], amount: u128, policy: RemainderPolicy) -> Vec<(String, u128)> {
+  split_rounded(claims, amount, Rounding::Down, policy)
+}
+
+fn split_rounded(
+  claims: &[(&str, u32)],
+  amount: u128,
+  rounding: Rounding,
+  policy: RemainderPolicy,
+) -> Vec<(String, u128)> {
+  distribution(claims)
+    .split_coins(&coins(&[("u", amount)]), rounding, &policy)
+    .unwrap()
+    .into_iter()
+    .map(|(addr, coins)| (addr.to_string(), coins.amount_of("u").u128()))
+    .collect()
+}
+
+fn amounts(amounts: &[(&str, u128)]) -> Vec<(String, u128)> {
+  amounts
+    .iter()
+    .map(|(addr, amount)| (addr.to_string(), *amount))
+    .collect()
+}
+
+const THIRDS: &[(&str, u32)] = &[("a", 3334), ("b", 3333), ("c", 3333)];
+
+#[test]
+fn largest_claim() {
+  assert_eq!(
+    split(THIRDS, 100, RemainderPolicy::LargestClaim),
+    amounts(&[("a", 34), ("b", 33), ("c", 33)])
+  );
+}
+
+#[test]
+fn largest_claim_tie_goes_to_lowest_address() {
+  let claims = &[("c", 4000), ("b", 4000), ("d", 2000)];
+  assert_eq!(
+    split(claims, 101, RemainderPolicy::LargestClaim),
+    amounts(&[("b", 41), ("c", 40), ("d", 20)])
+  );
+}
+
+#[test]
+fn largest_claim_takes_unclaimed_share() {
+  assert_eq!(
+    split(
+      &[("a", 3000), ("b", 2000)],
+      100,
+      RemainderPolicy::LargestClaim
+    ),
+    amounts(&[("a", 80), ("b", 20)])
+  );
+}
+
+#[test]
+fn to_claimant() {
+  assert_eq!(
+    split(THIRDS, 100, RemainderPolicy::To(Addr::unchecked("b"))),
+    amounts(&[("a", 33), ("b", 34), ("c", 33)])
+  );
+}
+
+#[test]
+fn to_other_address() {
+  assert_eq!(
+    split(THIRDS, 100, RemainderPolicy::To(Addr::unchecked("ab"))),
+    amounts(&[("a", 33), ("ab", 1), ("b", 33), ("c", 33)])
+  );
+}
+
+#[test]
+fn sender_keeps_remainder() {
+  assert_eq!(
+    split(THIRDS, 100, RemainderPolicy::Sender),
+    amounts(&[("a", 33), ("b", 33), ("c", 33)])
+  );
+}
+
+#[test]
+fn largest_fraction() {
+  let claims = &[("a", 1000), ("b", 4500), ("c", 4500)];
+  assert_eq!(
+    split(claims, 7, RemainderPolicy::LargestFraction),
+    amounts(&[("a", 1), ("b", 3), ("c", 3)])
+  );
+  assert_eq!(
+    split(claims, 7, RemainderPolicy::LargestClaim),
+    amounts(&[("b", 4), ("c", 3)])
+  );
+}
+
+#[test]
+fn largest_fraction_tie_goes_to_lowest_address() {
+  let claims = &[("c", 4000), ("b", 4000), ("d", 2000)];
+  assert_eq!(
+    split(claims, 101, RemainderPolicy::LargestFraction),
+    amounts(&[("b", 41), ("c", 40), ("d", 20)])
+  );
+}
+
+#[test]
+fn largest_fraction_spreads_unclaimed_share_pro_rata() {
+  assert_eq!(
+    split(
+      &[("a", 3000), ("b", 2000)],
+      100,
+      RemainderPolicy::LargestFraction
+    ),
+    amounts(&[("a", 60), ("b", 40)])
+  );
+  assert_eq!(
+    split(
+      &[("b", 3000), ("a", 3000)],
+      101,
+      RemainderPolicy::LargestFraction
+    ),
+    amounts(&[("a", 51), ("b", 50)])
+  );
+}
+
+#[test]
+fn refuse() {
+  let err = distribution(THIRDS)
+    .split_coins(
+      &coins(&[("u", 100)]),
+      Rounding::Down,
+      &RemainderPolicy::Refuse,
+    )
+    .unwrap_err();
+  assert!(matches!(
+    err,
+    XcosmError::Fund(FundError::RemainderRefused { .. })
+  ));
+  assert_eq!(
+    split(&[("a", 5000), ("b", 5000)], 100, RemainderPolicy::Refuse),
+    amounts(&[("a", 50), ("b", 50)])
+  );
+}
+
+#[test]
+fn remainder_is_allocated_per_denom() {
+  let claimed = distribution(&[("a", 5000), ("b", 5000)])
+    .split_coins(
+      &coins(&[("u", 3), ("v", 4)]),
+      Rounding::Down,
+      &RemainderPolicy::To(Addr::unchecked("z")),
+    )
+    .unwrap();
+  assert_eq!(
+    claimed,
+    vec![
+      (Addr::unchecked("a"), coins(&[("u", 1), ("v", 2)])),
+      (Addr::unchecked("b"), coins(&[("u", 1), ("v", 2)])),
+      (Addr::unchecked("z"), coins(&[("u", 1)])),
+    ]
+  );
+}
+
+#[test]
+fn rounding() {
+  let claims = &[("a", 2000), ("b", 4000), ("c", 4000)];
+  assert_eq!(
+    split_rounded(claims, 7, Rounding::HalfUp, RemainderPolicy::LargestClaim),
+    amounts(&[("a", 1), ("b", 3), ("c", 3)])
+  );
+  assert_eq!(
+    split(claims, 7, RemainderPolicy::LargestClaim),
+    amounts(&[("a", 1), ("b", 4), ("c", 2)])
+  );
+  assert_eq!(
+    split_rounded(&[("a", 3333)], 10, Rounding::Up, RemainderPolicy::Sender),
+    amounts(&[("a", 4)])
+  );
+}
+
+#[test]
+fn rounding_up_never_claims_more_than_funds() {
+  assert_eq!(
+    split_rounded(THIRDS, 100, Rounding::Up, RemainderPolicy::Refuse),
+    amounts(&[("a", 34), ("b", 33), ("c", 33)])
+  );
+  assert_eq!(
+    split_rounded(
+      &[("a", 5000), ("b", 5000)],
+      3,
+      Rounding::HalfUp,
+      RemainderPolicy::Refuse
+    ),
+    amounts(&[("a", 2), ("b", 1)])
+  );
+}
+
+fn weights_msg(api: &MockApi, weights: &[(&str, u128)]) -> WeightsMsg {
+  let entries = weights
+    .iter()
+    .map(|(addr, weight)| {
+      format!(
+        r#"{{"address":"{}","weight":"{}"}}"#,
+        api.addr_make(addr),
+        weight
+      )
+    })
+    .collect::<Vec<_>>();
+  from_json(format!("[{}]", entries.join(","))).unwrap()
+}
+
+#[test]
+fn weights_validation() {
+  let api = MockApi::default();
+  let validate = |weights: &[(&str, u128)]| -> XcosmResult<Weights> {
+    let msg = weights_msg(&api, weights);
+    (&api as &dyn Api).validate(&msg)
+  };
+  let weights = validate(&[("a", 1), ("b", 3)]).unwrap();
+  assert_eq!(weights.total_weight().unwrap().u128(), 4);
+  assert!(matches!(
+    validate(&[("a", 1), ("b", 0)]),
+    Err(XcosmError::Fund(FundError::ClaimZero { address })) if address == api.addr_make("b").as_str()
+  ));
+  assert!(matches!(
+    validate(&[]),
+    Err(XcosmError::Fund(FundError::DistributionUnclaimed {}))
+  ));
+}
+
+#[test]
+fn tree_validation() {
+  let api = MockApi::default();
+  let addr = |name: &str| ClaimTarget::Addr(api.addr_make(name).to_string());
+  let pool = |name: &str| ClaimTarget::Pool(name.to_string());
+  let claim = |target: ClaimTarget<String>, bps: u32| TreeClaim {
+    target,
+    bps: Claim::new(bps),
+  };
+  let validate = |root: Vec<TreeClaim<String>>, pools: Vec<(&str, Vec<TreeClaim<String>>)>| {
+    let msg = DistributionTreeMsg {
+      root,
+      pools: pools
+        .into_iter()
+        .map(|(name, claims)| (name.to_string(), claims))
+        .collect(),
+    };
+    (&api as &dyn Api)
+      .validate(&msg)
+      .map(|_: DistributionTree| ())
+  };
+  validate(
+    vec![claim(addr("a"), 5000), claim(pool("p"), 5000)],
+    vec![("p", vec![claim(addr("b"), 10000)])],
+  )
+  .unwrap();
+  assert!(matches!(
+    validate(vec![], vec![]),
+    Err(XcosmError::Fund(FundError::DistributionUnclaimed {}))
+  ));
+  assert!(matches!(
+    validate(
+      vec![claim(pool("p"), 10000)],
+      vec![("p", vec![claim(addr("a"), 0)])]
+    ),
+    Err(XcosmError::Fund(FundError::ClaimZero { .. }))
+  ));
+  assert!(matches!(
+    validate(vec![claim(pool("p"), 10000)], vec![]),
+    Err(XcosmError::Fund(FundError::PoolNotFound { pool })) if pool == "p"
+  ));
+  assert!(matches!(
+    validate(
+      vec![claim(pool("p"), 10000)],
+      vec![
+        ("p", vec![claim(pool("q"), 10000)]),
+        ("q", vec![claim(pool("p"), 10000)])
+      ]
+    ),
+    Err(XcosmError::Fund(FundError::PoolCycle { .. }))
+  ));
+  assert!(matches!(
+    validate(
+      vec![claim(pool("p"), 10000)],
+      vec![("p", vec![claim(addr("a"), 6000), claim(addr("b"), 6000)])]
+    ),
+    Err(XcosmError::Fund(FundError::DistributionOverclaimed { .. }))
+  ));
+  assert!(matches!(
+    validate(
+      vec![claim(addr("a"), 10000)],
+      vec![("p", vec![claim(pool("q"), 10000)]), ("q", vec![claim(pool("p"), 10000)])]
+    ),
+    Err(XcosmError::Fund(FundError::PoolUnused { pool })) if pool == "p"
+  ));
+}
+
+#[test]
+fn serializes_sorted_entries() {
+  let json = to_json_string(&distribution(&[("c", 1), ("a", 2), ("b", 3)])).unwrap();
+  assert_eq!(
+    json,
+    r#"[{"address":"a","bps":2},{"address":"b","bps":3},{"address":"c","bps":1}]"#
+  );
+}
+
+#[test]
+fn migrate_legacy_map_layout() {
+  const ITEM: Item<Distribution> = Item::new("distribution");
+  let mut storage = MockStorage::new();
+  storage.set(ITEM.as_slice(), br#"{"b":6000,"a":4000}"#);
+  let migrated = Distribution::migrate(&mut storage, &ITEM).unwrap();
+  assert_eq!(migrated, distribution(&[("a", 4000), ("b", 6000)]));
+  let expected = br#"[{"address":"a","bps":4000},{"address":"b","bps":6000}]"#;
+  assert_eq!(storage.get(ITEM.as_slice()).unwrap(), expected);
+
+  assert_eq!(
+    Distribution::migrate(&mut storage, &ITEM).unwrap(),
+    migrated
+  );
+  assert_eq!(storage.get(ITEM.as_slice()).unwrap(), expected);
+}
+
+#[test]
+fn migrate_missing_item() {
+  const ITEM: Item<Distribution> = Item::new("distribution");
+  assert!(Distribution::migrate(&mut MockStorage::new(), &ITEM).is_err());
+}
+
+fn take(
+  funds: &[(&str, u128)],
+  required: &[(&str, u128)],
+  unexpected: UnexpectedDenoms,
+) -> XcosmResult<(CoinSet, Option<CosmosMsg>)> {
+  let to_coins = |amounts: &[(&str, u128)]| {
+    amounts
+      .iter()
+      .map(|(denom, amount)| Coin::new(*amount, *denom))
+      .collect::<Vec<_>>()
+  };
+  message_info(&Addr::unchecked("sender"), &to_coins(funds))
+    .take_funds_with(to_coins(required), unexpected)
+}
+
+fn refund(amounts: &[(&str, u128)]) -> Option<CosmosMsg> {
+  Some(coins(amounts).send(&Addr::unchecked("sender")).unwrap())
+}
+
+#[test]
+fn take_funds_refunds_surplus_amount() {
+  for unexpected in [UnexpectedDenoms::Refund, UnexpectedDenoms::Reject] {
+    let (taken, msg) = take(&[("u", 10)], &[("u", 7)], unexpected).unwrap();
+    assert_eq!(taken, coins(&[("u", 7)]));
+    assert_eq!(msg, refund(&[("u", 3)]));
+  }
+  let (taken, msg) = take(&[("u", 7)], &[("u", 7)], UnexpectedDenoms::Reject).unwrap();
+  assert_eq!(taken, coins(&[("u", 7)]));
+  assert_eq!(msg, None);
+}
+
+#[test]
+fn take_funds_refunds_surplus_denom() {
+  let (taken, msg) = take(
+    &[("u", 10), ("v", 2)],
+    &[("u", 7)],
+    UnexpectedDenoms::Refund,
+  )
+  .unwrap();
+  assert_eq!(taken, coins(&[("u", 7)]));
+  assert_eq!(msg, refund(&[("u", 3), ("v", 2)]));
+}
+
+#[test]
+fn take_funds_rejects_surplus_denom() {
+  assert!(matches!(
+    take(&[("u", 7), ("v", 2)], &[("u", 7)], UnexpectedDenoms::Reject),
+    Err(XcosmError::Coin(CoinError::UnexpectedDenom { denom, received }))
+      if denom == "v" && received.u128() == 2
+  ));
+  let Err(XcosmError::Coin(CoinError::Mismatch { failures })) = take(
+    &[("u", 7), ("v", 2), ("w", 1)],
+    &[("u", 7)],
+    UnexpectedDenoms::Reject,
+  ) else {
+    panic!("expected a mismatch");
+  };
+  assert_eq!(
+    failures.iter().map(ToString::to_string).collect::<Vec<_>>(),
+    [
+      CoinError::UnexpectedDenom {
+        denom: "v".to_string(),
+        received: Uint128::new(2),
+      }
+      .to_string(),
+      CoinError::UnexpectedDenom {
+        denom: "w".to_string(),
+        received: Uint128::new(1),
+      }
+      .to_string(),
+    ]
+  );
+}
+
+#[test]
+fn take_funds_shortfall() {
+  for unexpected in [UnexpectedDenoms::Refund, UnexpectedDenoms::Reject] {
+    assert!(matches!(
+      take(&[("u", 5)], &[("u", 7)], unexpected),
+      Err(XcosmError::Coin(CoinError::Insufficient { denom, expected, received }))
+        if denom == "u" && expected.u128() == 7 && received.u128() == 5
+    ));
+    assert!(matches!(
+      take(&[("v", 5)], &[("u", 7)], unexpected),
+      Err(XcosmError::Coin(CoinError::Missing { denom, expected }))
+        if denom == "u" && expected.u128() == 7
+    ));
+  }
+}
+
+/// [`MockApi`] accepting addresses in any case, normalizing them to lowercase.
+struct CaseInsensitiveApi(MockApi);
+
+impl Api for CaseInsensitiveApi {
+  fn addr_validate(&self, human: &str) -> StdResult<Addr> {
+    self.0.addr_validate(&human.to_lowercase())
+  }
+
+  fn addr_canonicalize(&self, human: &str) -> StdResult<CanonicalAddr> {
+    self.0.addr_canonicalize(&human.to_lowercase())
+  }
+
+  fn addr_humanize(&self, canonical: &CanonicalAddr) -> StdResult<Addr> {
+    self.0.addr_humanize(canonical)
+  }
+
+  fn secp256k1_verify(&self, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, VerificationError> {
+    unimplemented!()
+  }
+
+  fn secp256k1_recover_pubkey(
+    &self,
+    _: &[u8],
+    _: &[u8],
+    _: u8,
+  ) -> Result<Vec<u8>, RecoverPubkeyError> {
+    unimplemented!()
+  }
+
+  fn ed25519_verify(&self, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, VerificationError> {
+    unimplemented!()
+  }
+
+  fn ed25519_batch_verify(
+    &self,
+    _: &[&[u8]],
+    _: &[&[u8]],
+    _: &[&[u8]],
+  ) -> Result<bool, VerificationError> {
+    unimplemented!()
+  }
+
+  fn debug(&self, _: &str) {}
+}
+
+#[test]
+fn builder_rejects_zero_claim() {
+  let api = MockApi::default();
+  assert!(matches!(
+    DistributionBuilder::new()
+      .claim(api.addr_make("a"), 5000)
+      .claim(api.addr_make("b"), 0)
+      .build(&api),
+    Err(XcosmError::Fund(FundError::ClaimZero { address })) if address == api.addr_make("b").as_str()
+  ));
+}
+
+#[test]
+fn builder_rejects_duplicate_after_normalization() {
+  let api = CaseInsensitiveApi(MockApi::default());
+  let addr = api.0.addr_make("a");
+  assert!(matches!(
+    DistributionBuilder::new()
+      .claim(addr.as_str(), 5000)
+      .claim(addr.as_str().to_uppercase(), 5000)
+      .build(&api),
+    Err(XcosmError::Fund(FundError::ClaimDuplicate { address })) if address == addr.as_str()
+  ));
+  let distribution = DistributionBuilder::new()
+    .claim(addr.as_str().to_uppercase(), 5000)
+    .build(&api)
+    .unwrap();
+  assert_eq!(distribution.get(&addr), Some(&Claim::new(5000)));
+}
+
+#[test]
+fn builder_rejects_overclaim() {
+  let api = MockApi::default();
+  let (a, b) = (api.addr_make("a"), api.addr_make("b"));
+  let last = a.clone().max(b.clone());
+  assert!(matches!(
+    DistributionBuilder::new()
+      .claim(a, 6000)
+      .claim(b, 5000)
+      .build(&api),
+    Err(XcosmError::Fund(FundError::DistributionOverclaimed { address })) if address == last.as_str()
+  ));
+  assert!(matches!(
+    DistributionBuilder::new().build(&api),
+    Err(XcosmError::Fund(FundError::DistributionUnclaimed {}))
+  ));
+}
+
+#[test]
+fn builder_require_full() {
+  let api = MockApi::default();
+  let builder = DistributionBuilder::new()
+    .claim(api.addr_make("a"), 6000)
+    .claim(api.addr_make("b"), 3000);
+  assert_eq!(
+    builder.clone().build(&api).unwrap().total_bps().unwrap(),
+    9000
+  );
+  assert!(matches!(
+    builder.clone().require_full().build(&api),
+    Err(XcosmError::Fund(FundError::DistributionUnderclaimed {
+      bps: 9000
+    }))
+  ));
+  builder
+    .claim(api.addr_make("c"), 1000)
+    .require_full()
+    .build(&api)
+    .unwrap();
+}
+
+#[test]
+fn denom_distribution_exact_denom_beats_prefix() {
+  let (exact, prefix) = (distribution(&[("a", 10000)]), distribution(&[("b", 10000)]));
+  let denoms = DenomDistribution::new(None)
+    .with_prefix("ibc/", prefix.clone())
+    .with_denom("ibc/x", exact.clone());
+  assert_eq!(denoms.distribution_for("ibc/x"), Some(&exact));
+  assert_eq!(denoms.distribution_for("ibc/y"), Some(&prefix));
+  assert_eq!(denoms.distribution_for("u"), None);
+}
+
+#[test]
+fn denom_distribution_longest_prefix_wins() {
+  let (short, long, default) = (
+    distribution(&[("a", 10000)]),
+    distribution(&[("b", 10000)]),
+    distribution(&[("c", 10000)]),
+  );
+  let denoms = DenomDistribution::new(Some(default.clone()))
+    .with_prefix("factory/creator/", long.clone())
+    .with_prefix("factory/", short.clone());
+  assert_eq!(
+    denoms.distribution_for("factory/creator/token"),
+    Some(&long)
+  );
+  assert_eq!(denoms.distribution_for("factory/other/token"), Some(&short));
+  assert_eq!(denoms.distribution_for("factory"), Some(&default));
+}
+
+#[test]
+fn denom_distribution_requires_default_for_unmatched_denom() {
+  let denoms = DenomDistribution::new(None).with_denom("u", distribution(&[("a", 10000)]));
+  assert!(matches!(
+    denoms.split_coins(&coins(&[("u", 10), ("v", 10)]), Rounding::Down, &RemainderPolicy::default()),
+    Err(XcosmError::Fund(FundError::DenomUnclaimed { denom })) if denom == "v"
+  ));
+}
+
+#[test]
+fn denom_distribution_sends_single_multi_send() {
+  let denoms = DenomDistribution::new(None)
+    .with_denom("u", distribution(&[("a", 10000)]))
+    .with_prefix("ibc/", distribution(&[("a", 5000), ("b", 5000)]));
+  let funds = coins(&[("u", 100), ("ibc/x", 10)]);
+  let from = Addr::unchecked("contract");
+  let msgs = denoms.distribute_coins(&from, &funds).unwrap();
+  assert_eq!(msgs.len(), 1);
+  let (a, b) = (Addr::unchecked("a"), Addr::unchecked("b"));
+  assert_eq!(
+    msgs,
+    funds
+      .send_many(
+        &from,
+        vec![
+          (&a, coins(&[("u", 100), ("ibc/x", 5)])),
+          (&b, coins(&[("ibc/x", 5)])),
+        ],
+      )
+      .unwrap()
+  );
+}
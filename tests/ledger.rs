@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{testing::MockStorage, Addr, Uint128};
+use xcosm::*;
+
+const LEDGER: ClaimLedger = ClaimLedger::new("ledger");
+
+fn addr(name: &str) -> Addr {
+  Addr::unchecked(name)
+}
+
+fn coins(amounts: &[(&str, u128)]) -> CoinSet {
+  CoinSet::new(
+    amounts
+      .iter()
+      .map(|(denom, amount)| (denom.to_string(), Uint128::new(*amount)))
+      .collect::<BTreeMap<_, _>>(),
+  )
+}
+
+#[test]
+fn credit_accumulates() {
+  let mut storage = MockStorage::new();
+  LEDGER
+    .credit(&mut storage, &addr("a"), &coins(&[("u", 5)]))
+    .unwrap();
+  LEDGER
+    .credit(&mut storage, &addr("a"), &coins(&[("u", 3), ("v", 1)]))
+    .unwrap();
+  LEDGER
+    .credit_all(
+      &mut storage,
+      &[
+        (addr("a"), coins(&[("v", 1)])),
+        (addr("b"), coins(&[("u", 2)])),
+      ],
+    )
+    .unwrap();
+  assert_eq!(
+    LEDGER.pending(&storage, &addr("a")).unwrap(),
+    coins(&[("u", 8), ("v", 2)])
+  );
+  assert_eq!(
+    LEDGER.query_pending(&storage, &addr("b")).unwrap(),
+    PendingResponse {
+      address: addr("b"),
+      pending: coins(&[("u", 2)]),
+    }
+  );
+  assert_eq!(
+    LEDGER.pending(&storage, &addr("c")).unwrap(),
+    CoinSet::default()
+  );
+}
+
+#[test]
+fn credit_distribution_credits_each_share() {
+  let mut storage = MockStorage::new();
+  let distribution = Distribution::new(BTreeMap::from([
+    (addr("a"), Claim::new(7500)),
+    (addr("b"), Claim::new(2500)),
+  ]));
+  let claimed = LEDGER
+    .credit_distribution(
+      &mut storage,
+      &distribution,
+      &coins(&[("u", 100)]),
+      Rounding::Down,
+      &RemainderPolicy::default(),
+    )
+    .unwrap();
+  assert_eq!(
+    claimed,
+    [
+      (addr("a"), coins(&[("u", 75)])),
+      (addr("b"), coins(&[("u", 25)]))
+    ]
+  );
+  assert_eq!(
+    LEDGER.pending(&storage, &addr("a")).unwrap(),
+    coins(&[("u", 75)])
+  );
+}
+
+#[test]
+fn withdraw_clears_balance() {
+  let mut storage = MockStorage::new();
+  let balance = coins(&[("u", 8), ("v", 2)]);
+  LEDGER.credit(&mut storage, &addr("a"), &balance).unwrap();
+  assert_eq!(
+    LEDGER.withdraw(&mut storage, &addr("a")).unwrap(),
+    balance.send(&addr("a")).unwrap()
+  );
+  assert_eq!(
+    LEDGER.pending(&storage, &addr("a")).unwrap(),
+    CoinSet::default()
+  );
+  assert!(LEDGER
+    .all_pending(&storage, None, None)
+    .unwrap()
+    .balances
+    .is_empty());
+  assert!(matches!(
+    LEDGER.withdraw(&mut storage, &addr("a")),
+    Err(XcosmError::Fund(FundError::NothingClaimable { address })) if address == "a"
+  ));
+}
+
+#[test]
+fn all_pending_is_paginated_by_address() {
+  let mut storage = MockStorage::new();
+  for name in ["c", "a", "d", "b"] {
+    LEDGER
+      .credit(&mut storage, &addr(name), &coins(&[("u", 1)]))
+      .unwrap();
+  }
+  let page = |start_after: Option<&str>, limit: Option<u32>| {
+    LEDGER
+      .all_pending(&storage, start_after.map(addr).as_ref(), limit)
+      .unwrap()
+      .balances
+      .into_iter()
+      .map(|balance| balance.address.to_string())
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(page(None, None), ["a", "b", "c", "d"]);
+  assert_eq!(page(None, Some(2)), ["a", "b"]);
+  assert_eq!(page(Some("b"), Some(2)), ["c", "d"]);
+  assert!(page(Some("d"), None).is_empty());
+}
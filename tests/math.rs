@@ -0,0 +1,307 @@
+use cosmwasm_std::{Coin, Decimal, Uint128, Uint256};
+use xcosm::*;
+
+/// Deterministic generator of coin sets mixing absent denoms, zero and small amounts, and
+/// amounts large enough that sums of three cannot overflow.
+struct Sets(u64);
+
+impl Sets {
+  fn next(&mut self) -> u64 {
+    self.0 = self
+      .0
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    self.0 >> 33
+  }
+
+  fn set(&mut self) -> CoinSet {
+    let mut set = CoinSet::default();
+    for denom in ["a", "b", "c", "d"] {
+      match self.next() % 3 {
+        0 => {}
+        1 => {
+          set.insert(denom.to_string(), Uint128::from(self.next() % 5));
+        }
+        _ => {
+          set.insert(denom.to_string(), Uint128::from(self.next()));
+        }
+      }
+    }
+    set
+  }
+
+  fn triples(seed: u64) -> impl Iterator<Item=(CoinSet, CoinSet, CoinSet)> {
+    let mut sets = Sets(seed);
+    (0..2000).map(move |_| (sets.set(), sets.set(), sets.set()))
+  }
+}
+
+fn has_no_zeros(set: &CoinSet) -> bool {
+  set.values().all(|amount| !amount.is_zero())
+}
+
+#[test]
+fn addition_is_commutative_and_associative() {
+  for (a, b, c) in Sets::triples(1) {
+    let ab = a.try_plus(&b).unwrap();
+    assert_eq!(ab, b.try_plus(&a).unwrap());
+    assert_eq!(
+      ab.try_plus(&c).unwrap(),
+      a.try_plus(&b.try_plus(&c).unwrap()).unwrap()
+    );
+  }
+}
+
+#[test]
+fn addition_is_a_union() {
+  for (a, b, _) in Sets::triples(2) {
+    let ab = a.try_plus(&b).unwrap();
+    assert!(has_no_zeros(&ab));
+    for denom in a.keys().chain(b.keys()) {
+      assert_eq!(ab.amount_of(denom), a.amount_of(denom) + b.amount_of(denom));
+    }
+  }
+}
+
+#[test]
+fn empty_set_is_the_identity() {
+  for (a, _, _) in Sets::triples(3) {
+    let sum = a.try_plus(&CoinSet::default()).unwrap();
+    assert!(has_no_zeros(&sum));
+    assert_eq!(sum, a);
+    assert!(a.try_minus(&a).unwrap().is_empty());
+  }
+}
+
+#[test]
+fn subtraction_inverts_addition() {
+  for (a, b, _) in Sets::triples(4) {
+    let ab = a.try_plus(&b).unwrap();
+    let diff = ab.try_minus(&b).unwrap();
+    assert!(has_no_zeros(&diff));
+    assert_eq!(diff, a);
+  }
+}
+
+#[test]
+fn subtraction_fails_atomically_on_underflow() {
+  for (a, b, _) in Sets::triples(5) {
+    let covered = b
+      .iter()
+      .all(|(denom, amount)| a.amount_of(denom) >= *amount);
+    let mut diff = a.clone();
+    match diff.try_minus_mut(&b) {
+      Ok(()) => {
+        assert!(covered);
+        assert!(has_no_zeros(&diff));
+        assert_eq!(diff.try_plus(&b).unwrap(), a);
+      }
+      Err(MathError::Container(ContainerError::Underflow {})) => {
+        assert!(!covered);
+        assert_eq!(
+          diff.into_iter().collect::<Vec<_>>(),
+          a.into_iter().collect::<Vec<_>>()
+        );
+      }
+      Err(err) => panic!("unexpected error: {err}"),
+    }
+  }
+}
+
+#[test]
+fn coin_operations_agree_with_set_operations() {
+  for (a, b, _) in Sets::triples(6) {
+    let mut sum = a.clone();
+    for (denom, amount) in b.iter() {
+      sum.try_plus_mut(&Coin::new(*amount, denom)).unwrap();
+    }
+    assert_eq!(sum, a.try_plus(&b).unwrap());
+  }
+}
+
+#[test]
+fn missing_denoms() {
+  let mut set = CoinSet::default();
+  set.try_plus_mut(&Coin::new(5u128, "x")).unwrap();
+  assert_eq!(set.amount_of("x").u128(), 5);
+  assert!(matches!(
+    set.try_minus(&Coin::new(1u128, "y")),
+    Err(MathError::Container(ContainerError::Underflow {}))
+  ));
+  assert_eq!(set.try_minus(&Coin::new(0u128, "y")).unwrap(), set);
+  set.try_plus_mut(&Coin::new(0u128, "y")).unwrap();
+  assert!(!set.contains_key("y"));
+}
+
+#[test]
+fn overflow_leaves_set_unchanged() {
+  let mut set = CoinSet::default();
+  set.insert("a".into(), Uint128::one());
+  set.insert("x".into(), Uint128::MAX);
+  let before = set.clone();
+  let mut add = CoinSet::default();
+  add.insert("a".into(), Uint128::one());
+  add.insert("x".into(), Uint128::one());
+  assert!(matches!(
+    set.try_plus_mut(&add),
+    Err(MathError::Container(ContainerError::Overflow {}))
+  ));
+  assert_eq!(set.amount_of("a"), before.amount_of("a"));
+  assert_eq!(set.amount_of("x"), before.amount_of("x"));
+}
+
+#[test]
+fn multiplication() {
+  let coin = Coin::new(7u128, "a");
+  assert_eq!(
+    coin.try_mul(Uint128::new(3)).unwrap(),
+    Coin::new(21u128, "a")
+  );
+  assert!(Coin::new(Uint128::MAX, "a")
+    .try_mul(Uint128::new(2))
+    .is_err());
+  assert_eq!(
+    coin
+      .try_mul((Decimal::percent(50), Rounding::Down))
+      .unwrap(),
+    Coin::new(3u128, "a")
+  );
+  assert_eq!(
+    coin.try_mul((Decimal::percent(50), Rounding::Up)).unwrap(),
+    Coin::new(4u128, "a")
+  );
+  let mut set = CoinSet::default();
+  set.insert("a".into(), Uint128::new(1));
+  set.insert("b".into(), Uint128::new(4));
+  let half = set.try_mul((Decimal::percent(50), Rounding::Down)).unwrap();
+  assert!(!half.contains_key("a"));
+  assert_eq!(half.amount_of("b").u128(), 2);
+  assert_eq!(
+    set.try_mul(Uint128::new(2)).unwrap().amount_of("b").u128(),
+    8
+  );
+}
+
+#[test]
+fn division() {
+  let coin = Coin::new(7u128, "a");
+  assert_eq!(
+    coin.try_div(Uint128::new(2), Rounding::Down).unwrap(),
+    Coin::new(3u128, "a")
+  );
+  assert_eq!(
+    coin.try_div(Uint128::new(2), Rounding::Up).unwrap(),
+    Coin::new(4u128, "a")
+  );
+  assert!(coin.try_div(Uint128::zero(), Rounding::Down).is_err());
+  let mut set = CoinSet::default();
+  set.insert("a".into(), Uint128::new(1));
+  set.insert("b".into(), Uint128::new(9));
+  let third = set.try_div(Uint128::new(3), Rounding::Down).unwrap();
+  assert!(!third.contains_key("a"));
+  assert_eq!(third.amount_of("b").u128(), 3);
+}
+
+#[test]
+fn multiplication_by_ratio() {
+  let coin = Coin::new(10u128, "a");
+  assert_eq!(
+    coin
+      .try_mul_ratio(Uint128::new(2), Uint128::new(3), Rounding::Down)
+      .unwrap(),
+    Coin::new(6u128, "a")
+  );
+  assert_eq!(
+    coin
+      .try_mul_ratio(Uint128::new(2), Uint128::new(3), Rounding::Up)
+      .unwrap(),
+    Coin::new(7u128, "a")
+  );
+  assert!(coin
+    .try_mul_ratio(Uint128::one(), Uint128::zero(), Rounding::Down)
+    .is_err());
+  // the result must still fit
+  assert!(coin
+    .try_mul_ratio(Uint128::MAX, Uint128::one(), Rounding::Down)
+    .is_err());
+}
+
+#[test]
+fn multiplication_by_ratio_avoids_intermediate_overflow() {
+  let max = Coin::new(Uint128::MAX, "a");
+  assert_eq!(
+    max
+      .try_mul_ratio(Uint128::new(3), Uint128::new(4), Rounding::Down)
+      .unwrap()
+      .amount,
+    Uint128::MAX / Uint128::new(4) * Uint128::new(3) + Uint128::new(2)
+  );
+  assert_eq!(
+    max.try_mul((Decimal::percent(75), Rounding::Down)).unwrap(),
+    max
+      .try_mul_ratio(Uint128::new(3), Uint128::new(4), Rounding::Down)
+      .unwrap()
+  );
+  assert_eq!(
+    Uint128::MAX
+      .mul_div(Uint128::new(3), Uint128::new(4), Rounding::Up)
+      .unwrap(),
+    Uint128::MAX / Uint128::new(4) * Uint128::new(3) + Uint128::new(3)
+  );
+}
+
+/// `numerator / denominator` rounded by each of Down, Up, HalfUp, HalfEven and TowardZero.
+const ROUNDING_TABLE: &[(u128, u128, [u128; 5])] = &[
+  (0, 2, [0, 0, 0, 0, 0]),
+  (1, 3, [0, 1, 0, 0, 0]),
+  (2, 3, [0, 1, 1, 1, 0]),
+  (5, 2, [2, 3, 3, 2, 2]),
+  (7, 2, [3, 4, 4, 4, 3]),
+  (6, 3, [2, 2, 2, 2, 2]),
+];
+
+const ROUNDINGS: [Rounding; 5] = [
+  Rounding::Down,
+  Rounding::Up,
+  Rounding::HalfUp,
+  Rounding::HalfEven,
+  Rounding::TowardZero,
+];
+
+#[test]
+fn rounding_modes() {
+  for &(numerator, denominator, expected) in ROUNDING_TABLE {
+    for (rounding, expected) in ROUNDINGS.into_iter().zip(expected) {
+      let case = format!("{numerator}/{denominator} {rounding:?}");
+      assert_eq!(
+        Uint128::new(numerator)
+          .mul_div(Uint128::one(), Uint128::new(denominator), rounding)
+          .unwrap(),
+        Uint128::new(expected),
+        "Uint128 {case}"
+      );
+      assert_eq!(
+        Uint256::from(numerator)
+          .mul_div(Uint256::one(), Uint256::from(denominator), rounding)
+          .unwrap(),
+        Uint256::from(expected),
+        "Uint256 {case}"
+      );
+      assert_eq!(
+        rounding
+          .div(Uint256::from(numerator), Uint256::from(denominator))
+          .unwrap(),
+        Uint256::from(expected),
+        "Rounding::div {case}"
+      );
+      // Decimal rounds in its smallest unit
+      assert_eq!(
+        Decimal::raw(numerator)
+          .mul_div(Decimal::raw(1), Decimal::raw(denominator), rounding)
+          .unwrap(),
+        Decimal::raw(expected),
+        "Decimal {case}"
+      );
+    }
+  }
+}
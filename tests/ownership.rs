@@ -0,0 +1,193 @@
+use cosmwasm_std::{
+  testing::{mock_env, MockStorage},
+  Addr, Attribute, BlockInfo, Event,
+};
+use xcosm::*;
+
+const OWNERSHIP: Ownership = Ownership::new("ownership");
+
+fn addr(name: &str) -> Addr {
+  Addr::unchecked(name)
+}
+
+fn attributes(event: &Event) -> Vec<(&str, &str)> {
+  event
+    .attributes
+    .iter()
+    .map(|Attribute { key, value }| (key.as_str(), value.as_str()))
+    .collect()
+}
+
+/// Storage owned by "owner", with a transfer to "next" pending until 10 blocks after the
+/// returned block.
+fn pending() -> (MockStorage, BlockInfo) {
+  let mut storage = MockStorage::new();
+  let block = mock_env().block;
+  OWNERSHIP.initialize(&mut storage, addr("owner")).unwrap();
+  OWNERSHIP
+    .propose_transfer(
+      &mut storage,
+      &addr("owner"),
+      addr("next"),
+      Expiry::AtHeight(block.height + 10),
+      &block,
+    )
+    .unwrap();
+  (storage, block)
+}
+
+fn at_height(block: &BlockInfo, height: u64) -> BlockInfo {
+  BlockInfo {
+    height,
+    ..block.clone()
+  }
+}
+
+#[test]
+fn only_owner_proposes_and_cancels() {
+  let (mut storage, block) = pending();
+  assert!(matches!(
+    OWNERSHIP.propose_transfer(
+      &mut storage,
+      &addr("next"),
+      addr("next"),
+      Expiry::Never,
+      &block
+    ),
+    Err(XcosmError::Auth(AuthError::NotOwner {}))
+  ));
+  assert!(matches!(
+    OWNERSHIP.cancel_transfer(&mut storage, &addr("next")),
+    Err(XcosmError::Auth(AuthError::NotOwner {}))
+  ));
+  let event = OWNERSHIP
+    .cancel_transfer(&mut storage, &addr("owner"))
+    .unwrap();
+  assert_eq!(
+    attributes(&event),
+    [
+      ("action", "cancel_transfer"),
+      ("owner", "owner"),
+      ("pending_owner", "next")
+    ]
+  );
+  assert_eq!(OWNERSHIP.load(&storage).unwrap().pending, None);
+  assert!(matches!(
+    OWNERSHIP.cancel_transfer(&mut storage, &addr("owner")),
+    Err(XcosmError::Auth(AuthError::NoPendingOwner {}))
+  ));
+}
+
+#[test]
+fn propose_rejects_reached_expiry() {
+  let (mut storage, block) = pending();
+  assert!(matches!(
+    OWNERSHIP.propose_transfer(
+      &mut storage,
+      &addr("owner"),
+      addr("other"),
+      Expiry::AtHeight(block.height),
+      &block
+    ),
+    Err(XcosmError::Auth(AuthError::TransferExpired { .. }))
+  ));
+}
+
+#[test]
+fn only_pending_owner_accepts() {
+  let (mut storage, block) = pending();
+  assert!(matches!(
+    OWNERSHIP.accept_transfer(&mut storage, &addr("owner"), &block),
+    Err(XcosmError::Auth(AuthError::NotPendingOwner {}))
+  ));
+  let event = OWNERSHIP
+    .accept_transfer(&mut storage, &addr("next"), &block)
+    .unwrap();
+  assert_eq!(
+    attributes(&event),
+    [
+      ("action", "accept_transfer"),
+      ("previous_owner", "owner"),
+      ("owner", "next")
+    ]
+  );
+  let state = OWNERSHIP.load(&storage).unwrap();
+  assert_eq!(state.owner, Some(addr("next")));
+  assert_eq!(state.pending, None);
+  OWNERSHIP.require_owner(&storage, &addr("next")).unwrap();
+  assert!(matches!(
+    OWNERSHIP.accept_transfer(&mut storage, &addr("next"), &block),
+    Err(XcosmError::Auth(AuthError::NoPendingOwner {}))
+  ));
+}
+
+#[test]
+fn accept_rejected_at_and_after_expiry() {
+  let (mut storage, block) = pending();
+  for height in [block.height + 10, block.height + 11] {
+    assert!(matches!(
+      OWNERSHIP.accept_transfer(&mut storage, &addr("next"), &at_height(&block, height)),
+      Err(XcosmError::Auth(AuthError::TransferExpired { expiry })) if expiry == Expiry::AtHeight(block.height + 10)
+    ));
+  }
+  OWNERSHIP
+    .accept_transfer(
+      &mut storage,
+      &addr("next"),
+      &at_height(&block, block.height + 9),
+    )
+    .unwrap();
+}
+
+#[test]
+fn renounce_clears_pending_transfer() {
+  let (mut storage, block) = pending();
+  assert!(matches!(
+    OWNERSHIP.renounce(&mut storage, &addr("next")),
+    Err(XcosmError::Auth(AuthError::NotOwner {}))
+  ));
+  let event = OWNERSHIP.renounce(&mut storage, &addr("owner")).unwrap();
+  assert_eq!(
+    attributes(&event),
+    [("action", "renounce"), ("previous_owner", "owner")]
+  );
+  assert_eq!(OWNERSHIP.load(&storage).unwrap(), OwnershipState::default());
+  assert!(matches!(
+    OWNERSHIP.accept_transfer(&mut storage, &addr("next"), &block),
+    Err(XcosmError::Auth(AuthError::NoPendingOwner {}))
+  ));
+  assert_eq!(
+    OWNERSHIP.load(&storage).unwrap().authorized(),
+    Authorized::None
+  );
+}
+
+#[test]
+fn initialize_and_propose_events() {
+  let mut storage = MockStorage::new();
+  let block = mock_env().block;
+  let event = OWNERSHIP.initialize(&mut storage, addr("owner")).unwrap();
+  assert_eq!(event.ty, OWNERSHIP_EVENT);
+  assert_eq!(
+    attributes(&event),
+    [("action", "initialize"), ("owner", "owner")]
+  );
+  let event = OWNERSHIP
+    .propose_transfer(
+      &mut storage,
+      &addr("owner"),
+      addr("next"),
+      Expiry::AtHeight(block.height + 10),
+      &block,
+    )
+    .unwrap();
+  assert_eq!(
+    attributes(&event),
+    [
+      ("action", "propose_transfer"),
+      ("owner", "owner"),
+      ("pending_owner", "next"),
+      ("expiry", &format!("height {}", block.height + 10))
+    ]
+  );
+}
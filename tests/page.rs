@@ -0,0 +1,21 @@
+use cosmwasm_std::testing::MockStorage;
+use cw_storage_plus::Map;
+use xcosm::*;
+
+const MAP: Map<u32, u32> = Map::new("map");
+
+#[test]
+fn paginate_limits_and_resumes() {
+  let mut storage = MockStorage::new();
+  for key in 0..50 {
+    MAP.save(&mut storage, key, &(key * 2)).unwrap();
+  }
+  let page = paginate(&storage, &MAP, None, None).unwrap();
+  assert_eq!(page.len(), DEFAULT_PAGE_LIMIT as usize);
+  assert_eq!(page[0], (0, 0));
+  let page = paginate(&storage, &MAP, Some(9), Some(100)).unwrap();
+  assert_eq!(page.len(), MAX_PAGE_LIMIT as usize);
+  assert_eq!(page[0], (10, 20));
+  let page = paginate(&storage, &MAP, Some(47), Some(5)).unwrap();
+  assert_eq!(page, [(48, 96), (49, 98)]);
+}
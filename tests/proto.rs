@@ -0,0 +1,120 @@
+use cosmwasm_std::{Addr, Coin};
+use xcosm::*;
+
+fn hex(s: &str) -> Vec<u8> {
+  let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+  (0..s.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+    .collect()
+}
+
+fn multi_send() -> BankMsgMultiSend {
+  BankMsgMultiSend {
+    inputs: vec![BankMsgIo::new(
+      Addr::unchecked("a"),
+      vec![Coin::new(1u128, "u")],
+    )],
+    outputs: vec![BankMsgIo::new(
+      Addr::unchecked("b"),
+      vec![Coin::new(1u128, "u")],
+    )],
+  }
+}
+
+#[test]
+fn coin_golden() {
+  let coin = Coin::new(1234u128, "ucosm");
+  assert_eq!(coin.encode(), hex("0a05 75636f736d 1204 31323334"));
+  assert_eq!(Coin::decode(&coin.encode()).unwrap(), coin);
+}
+
+#[test]
+fn multi_send_golden() {
+  let msg = multi_send();
+  let encoded = msg.encode();
+  assert_eq!(
+    encoded,
+    hex("0a0b 0a0161 1206 0a0175 120131 120b 0a0162 1206 0a0175 120131")
+  );
+  assert_eq!(BankMsgMultiSend::decode(&encoded).unwrap(), msg);
+}
+
+#[test]
+fn multi_send_any_round_trip() {
+  let msg = multi_send();
+  let any = msg.clone().into_any_msg();
+  assert_eq!(any.type_url, "/cosmos.bank.v1beta1.MsgMultiSend");
+  assert_eq!(any.value.as_slice(), msg.encode().as_slice());
+  assert_eq!(BankMsgMultiSend::from_any_msg(&any).unwrap(), msg);
+}
+
+#[test]
+fn decode_skips_unknown_fields() {
+  let mut encoded = multi_send().encode();
+  // varint, fixed64, fixed32 and length-delimited fields 3, 4, 5 and 7
+  encoded.extend(hex("18ac02 210102030405060708 2d01020304 3a0161"));
+  assert_eq!(BankMsgMultiSend::decode(&encoded).unwrap(), multi_send());
+}
+
+#[test]
+fn decode_rejects_truncated_input() {
+  let encoded = multi_send().encode();
+  assert!(matches!(
+    BankMsgMultiSend::decode(&encoded[..encoded.len() - 1]),
+    Err(ProtoError::Truncated {})
+  ));
+}
+
+#[test]
+fn varint_round_trip() {
+  for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+    let mut writer = ProtoWriter::default();
+    writer.varint(value);
+    let bytes = writer.into_bytes();
+    assert_eq!(ProtoReader::new(&bytes).varint().unwrap(), value);
+  }
+}
+
+#[cfg(feature = "cosmwasm_2_0")]
+#[test]
+fn send_coins_many_encodes_multi_send() {
+  use cosmwasm_std::CosmosMsg;
+
+  let coins: CoinSet = vec![Coin::new(2u128, "u")].try_into().unwrap();
+  let one: CoinSet = vec![Coin::new(1u128, "u")].try_into().unwrap();
+  let (a, b, c) = (
+    Addr::unchecked("a"),
+    Addr::unchecked("b"),
+    Addr::unchecked("c"),
+  );
+  let msgs = send_coins_many(&coins, &a, vec![(&b, one.clone()), (&c, one)]).unwrap();
+  let [CosmosMsg::Any(any)] = &msgs[..] else {
+    panic!("expected a single Any message, got {msgs:?}");
+  };
+  let msg = BankMsgMultiSend::from_any_msg(any).unwrap();
+  assert_eq!(msg.inputs[0].coins(), &[Coin::new(2u128, "u")]);
+  assert_eq!(msg.outputs.len(), 2);
+}
+
+#[test]
+fn timestamp_range() {
+  use cosmwasm_std::Timestamp;
+
+  let max = Timestamp::from_nanos(u64::MAX);
+  assert_eq!(Timestamp::decode(&max.encode()).unwrap(), max);
+  // seconds = 18446744073, nanos = 999999999 overflows u64 nanos
+  let mut writer = ProtoWriter::default();
+  writer.uint64(1, 18_446_744_073);
+  writer.uint64(2, 999_999_999);
+  assert!(matches!(
+    Timestamp::decode(&writer.into_bytes()),
+    Err(ProtoError::InvalidField { field: 1, .. })
+  ));
+  let mut writer = ProtoWriter::default();
+  writer.uint64(2, 1_000_000_000);
+  assert!(matches!(
+    Timestamp::decode(&writer.into_bytes()),
+    Err(ProtoError::InvalidField { field: 2, .. })
+  ));
+}
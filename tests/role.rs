@@ -0,0 +1,196 @@
+use cosmwasm_std::{testing::MockStorage, Addr};
+use xcosm::*;
+
+const ROLES: Roles<String> = Roles::new("roles");
+
+fn addr(name: &str) -> Addr {
+  Addr::unchecked(name)
+}
+
+fn role(name: &str) -> String {
+  name.to_string()
+}
+
+/// Storage with "admin" held by "root" and administering "minter", held by "alice".
+fn roles() -> MockStorage {
+  let mut storage = MockStorage::new();
+  ROLES
+    .set_role(
+      &mut storage,
+      role("admin"),
+      Authorized::One(addr("root")),
+      None,
+    )
+    .unwrap();
+  ROLES
+    .set_role(
+      &mut storage,
+      role("minter"),
+      Authorized::One(addr("alice")),
+      Some(role("admin")),
+    )
+    .unwrap();
+  storage
+}
+
+fn is_missing_role<T: std::fmt::Debug>(res: XcosmResult<T>, expected: &str) -> bool {
+  matches!(res, Err(XcosmError::Auth(AuthError::MissingRole { role })) if role == expected)
+}
+
+#[test]
+fn admin_grants_and_revokes() {
+  let mut storage = roles();
+  let minter = role("minter");
+  assert!(ROLES
+    .grant(&mut storage, &addr("root"), &minter, addr("bob"))
+    .unwrap());
+  assert!(!ROLES
+    .grant(&mut storage, &addr("root"), &minter, addr("bob"))
+    .unwrap());
+  ROLES.require_role(&storage, &minter, &addr("bob")).unwrap();
+  assert!(ROLES
+    .revoke(&mut storage, &addr("root"), &minter, &addr("alice"))
+    .unwrap());
+  assert!(!ROLES
+    .revoke(&mut storage, &addr("root"), &minter, &addr("alice"))
+    .unwrap());
+  assert!(!ROLES.has_role(&storage, &minter, &addr("alice")).unwrap());
+}
+
+#[test]
+fn non_admin_cannot_change_role() {
+  let mut storage = roles();
+  let minter = role("minter");
+  let before = ROLES.load(&storage, &minter).unwrap();
+  assert!(is_missing_role(
+    ROLES.grant(&mut storage, &addr("alice"), &minter, addr("bob")),
+    "admin"
+  ));
+  assert!(is_missing_role(
+    ROLES.revoke(&mut storage, &addr("alice"), &minter, &addr("alice")),
+    "admin"
+  ));
+  assert!(is_missing_role(
+    ROLES.set_admin(&mut storage, &addr("alice"), &minter, None),
+    "admin"
+  ));
+  assert_eq!(ROLES.load(&storage, &minter).unwrap(), before);
+}
+
+#[test]
+fn set_admin_hands_over_administration() {
+  let mut storage = roles();
+  let minter = role("minter");
+  ROLES
+    .set_admin(&mut storage, &addr("root"), &minter, Some(minter.clone()))
+    .unwrap();
+  ROLES
+    .grant(&mut storage, &addr("alice"), &minter, addr("bob"))
+    .unwrap();
+  assert!(is_missing_role(
+    ROLES.grant(&mut storage, &addr("root"), &minter, addr("carol")),
+    "minter"
+  ));
+}
+
+#[test]
+fn role_without_admin() {
+  let mut storage = roles();
+  for res in [
+    ROLES
+      .grant(&mut storage, &addr("root"), &role("admin"), addr("bob"))
+      .map(|_| ()),
+    ROLES
+      .revoke(&mut storage, &addr("root"), &role("admin"), &addr("root"))
+      .map(|_| ()),
+    ROLES.set_admin(&mut storage, &addr("root"), &role("admin"), None),
+    ROLES
+      .grant(&mut storage, &addr("root"), &role("unset"), addr("bob"))
+      .map(|_| ()),
+  ] {
+    assert!(matches!(
+      res,
+      Err(XcosmError::Auth(AuthError::NoRoleAdmin { .. }))
+    ));
+  }
+}
+
+#[test]
+fn renounce() {
+  let mut storage = roles();
+  let minter = role("minter");
+  assert!(is_missing_role(
+    ROLES.renounce(&mut storage, &addr("bob"), &minter),
+    "minter"
+  ));
+  ROLES
+    .renounce(&mut storage, &addr("alice"), &minter)
+    .unwrap();
+  assert!(is_missing_role(
+    ROLES.renounce(&mut storage, &addr("alice"), &minter),
+    "minter"
+  ));
+  assert!(is_missing_role(
+    ROLES.require_role(&storage, &minter, &addr("alice")),
+    "minter"
+  ));
+}
+
+#[test]
+fn revoking_last_member_leaves_none() {
+  let mut storage = roles();
+  let minter = role("minter");
+  ROLES
+    .set_role(
+      &mut storage,
+      minter.clone(),
+      Authorized::Many(vec![addr("alice")]),
+      Some(role("admin")),
+    )
+    .unwrap();
+  ROLES
+    .revoke(&mut storage, &addr("root"), &minter, &addr("alice"))
+    .unwrap();
+  assert_eq!(
+    ROLES.load(&storage, &minter).unwrap().members,
+    Authorized::None
+  );
+  ROLES
+    .grant(&mut storage, &addr("root"), &minter, addr("bob"))
+    .unwrap();
+  ROLES
+    .grant(&mut storage, &addr("root"), &minter, addr("carol"))
+    .unwrap();
+  for member in ["bob", "carol"] {
+    ROLES
+      .revoke(&mut storage, &addr("root"), &minter, &addr(member))
+      .unwrap();
+  }
+  assert_eq!(
+    ROLES.load(&storage, &minter).unwrap().members,
+    Authorized::None
+  );
+}
+
+#[test]
+fn list_is_paginated_by_role_key() {
+  let mut storage = roles();
+  for name in ["c", "a", "b"] {
+    ROLES
+      .set_role(&mut storage, role(name), Authorized::None, None)
+      .unwrap();
+  }
+  let names = |start_after: Option<&str>, limit: Option<u32>| {
+    ROLES
+      .list(&storage, start_after.map(role).as_ref(), limit)
+      .unwrap()
+      .roles
+      .into_iter()
+      .map(|role| role.role)
+      .collect::<Vec<_>>()
+  };
+  assert_eq!(names(None, None), ["a", "admin", "b", "c", "minter"]);
+  assert_eq!(names(None, Some(2)), ["a", "admin"]);
+  assert_eq!(names(Some("admin"), Some(2)), ["b", "c"]);
+  assert_eq!(names(Some("c"), None), ["minter"]);
+}
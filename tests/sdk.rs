@@ -0,0 +1,222 @@
+use std::fmt::Debug;
+
+use cosmwasm_std::{AnyMsg, Coin, Timestamp};
+use xcosm::*;
+
+fn hex(s: &str) -> Vec<u8> {
+  let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+  (0..s.len())
+    .step_by(2)
+    .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+    .collect()
+}
+
+fn coin(amount: u128, denom: &str) -> Coin {
+  Coin::new(amount, denom)
+}
+
+/// Encode `msg` as an [`AnyMsg`] and decode it back, checking the type url and value.
+fn round_trip<T: ProtoAny+Clone+PartialEq+Debug>(msg: T) {
+  let any = msg.clone().into_any_msg();
+  assert_eq!(any.type_url, T::TYPE_URL);
+  assert_eq!(any.value.as_slice(), msg.encode().as_slice());
+  assert_eq!(T::from_any_msg(&any).unwrap(), msg);
+  assert!(T::from_any_msg(&AnyMsg {
+    type_url: "/wrong".to_string(),
+    value: any.value,
+  })
+  .is_err());
+}
+
+fn generic_authorization() -> AnyMsg {
+  GenericAuthorization {
+    msg: "m".to_string(),
+  }
+  .into_any_msg()
+}
+
+fn msg_grant() -> MsgGrant {
+  MsgGrant {
+    granter: "a".to_string(),
+    grantee: "b".to_string(),
+    grant: Grant {
+      authorization: Some(generic_authorization()),
+      expiration: Some(Timestamp::from_nanos(1_000_000_002)),
+    },
+  }
+}
+
+fn msg_exec() -> MsgExec {
+  MsgExec {
+    grantee: "g".to_string(),
+    msgs: vec![MsgRevokeAllowance {
+      granter: "a".to_string(),
+      grantee: "b".to_string(),
+    }
+    .into_any_msg()],
+  }
+}
+
+fn basic_allowance() -> BasicAllowance {
+  BasicAllowance {
+    spend_limit: vec![coin(5, "u"), coin(10, "v")],
+    expiration: Some(Timestamp::from_seconds(300)),
+  }
+}
+
+#[test]
+fn msg_delegate_golden() {
+  let msg = MsgDelegate {
+    delegator_address: "d".to_string(),
+    validator_address: "v".to_string(),
+    amount: coin(5, "u"),
+  };
+  assert_eq!(msg.encode(), hex("0a0164 120176 1a06 0a0175 120135"));
+  assert_eq!(MsgDelegate::decode(&msg.encode()).unwrap(), msg);
+}
+
+#[test]
+fn msg_begin_redelegate_golden() {
+  let msg = MsgBeginRedelegate {
+    delegator_address: "d".to_string(),
+    validator_src_address: "s".to_string(),
+    validator_dst_address: "t".to_string(),
+    amount: coin(5, "u"),
+  };
+  assert_eq!(msg.encode(), hex("0a0164 120173 1a0174 2206 0a0175 120135"));
+  assert_eq!(MsgBeginRedelegate::decode(&msg.encode()).unwrap(), msg);
+}
+
+#[test]
+fn msg_vote_golden() {
+  let msg = MsgVote {
+    proposal_id: 7,
+    voter: "v".to_string(),
+    option: VoteOption::Yes,
+    metadata: "m".to_string(),
+  };
+  assert_eq!(msg.encode(), hex("0807 120176 1801 22016d"));
+  assert_eq!(MsgVote::decode(&msg.encode()).unwrap(), msg);
+  assert!(matches!(
+    MsgVote::decode(&hex("1805")),
+    Err(ProtoError::InvalidField { field: 3, .. })
+  ));
+}
+
+#[test]
+fn msg_grant_golden() {
+  let any = [
+    hex("0a2a"),
+    b"/cosmos.authz.v1beta1.GenericAuthorization".to_vec(),
+    hex("1203 0a016d"),
+  ]
+  .concat();
+  let grant = [hex("0a31"), any, hex("1204 0801 1002")].concat();
+  let expected = [hex("0a0161 120162 1a39"), grant].concat();
+  let msg = msg_grant();
+  assert_eq!(msg.encode(), expected);
+  assert_eq!(MsgGrant::decode(&expected).unwrap(), msg);
+}
+
+#[test]
+fn msg_exec_golden() {
+  let expected = [
+    hex("0a0167 1235 0a2b"),
+    b"/cosmos.feegrant.v1beta1.MsgRevokeAllowance".to_vec(),
+    hex("1206 0a0161 120162"),
+  ]
+  .concat();
+  let msg = msg_exec();
+  assert_eq!(msg.encode(), expected);
+  assert_eq!(MsgExec::decode(&expected).unwrap(), msg);
+}
+
+#[test]
+fn basic_allowance_golden() {
+  let msg = basic_allowance();
+  assert_eq!(
+    msg.encode(),
+    hex("0a06 0a0175 120135 0a07 0a0176 12023130 1203 08ac02")
+  );
+  assert_eq!(BasicAllowance::decode(&msg.encode()).unwrap(), msg);
+}
+
+#[test]
+fn any_round_trips() {
+  let (a, b) = ("a".to_string(), "b".to_string());
+  round_trip(MsgDelegate {
+    delegator_address: a.clone(),
+    validator_address: b.clone(),
+    amount: coin(5, "u"),
+  });
+  round_trip(MsgUndelegate {
+    delegator_address: a.clone(),
+    validator_address: b.clone(),
+    amount: coin(5, "u"),
+  });
+  round_trip(MsgBeginRedelegate {
+    delegator_address: a.clone(),
+    validator_src_address: b.clone(),
+    validator_dst_address: "c".to_string(),
+    amount: coin(5, "u"),
+  });
+  round_trip(MsgSetWithdrawAddress {
+    delegator_address: a.clone(),
+    withdraw_address: b.clone(),
+  });
+  round_trip(MsgWithdrawDelegatorReward {
+    delegator_address: a.clone(),
+    validator_address: b.clone(),
+  });
+  round_trip(MsgFundCommunityPool {
+    amount: vec![coin(5, "u"), coin(6, "v")],
+    depositor: a.clone(),
+  });
+  round_trip(GenericAuthorization {
+    msg: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+  });
+  round_trip(msg_grant());
+  round_trip(MsgGrant {
+    granter: a.clone(),
+    grantee: b.clone(),
+    grant: Grant {
+      authorization: None,
+      expiration: None,
+    },
+  });
+  round_trip(msg_exec());
+  round_trip(MsgRevoke {
+    granter: a.clone(),
+    grantee: b.clone(),
+    msg_type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(),
+  });
+  round_trip(basic_allowance());
+  round_trip(MsgGrantAllowance {
+    granter: a.clone(),
+    grantee: b.clone(),
+    allowance: Some(basic_allowance().into_any_msg()),
+  });
+  round_trip(MsgRevokeAllowance {
+    granter: a.clone(),
+    grantee: b.clone(),
+  });
+  for option in [
+    VoteOption::Unspecified,
+    VoteOption::Yes,
+    VoteOption::Abstain,
+    VoteOption::No,
+    VoteOption::NoWithVeto,
+  ] {
+    round_trip(MsgVote {
+      proposal_id: 1,
+      voter: a.clone(),
+      option,
+      metadata: String::new(),
+    });
+  }
+  round_trip(MsgDeposit {
+    proposal_id: u64::MAX,
+    depositor: a,
+    amount: vec![coin(5, "u")],
+  });
+}
@@ -0,0 +1,73 @@
+use cosmwasm_std::{testing::MockStorage, Addr};
+use xcosm::*;
+
+const MEMBERS: StoredAuthorized = StoredAuthorized::new("members");
+
+fn addrs(names: &[&str]) -> Vec<Addr> {
+  names.iter().map(|name| Addr::unchecked(*name)).collect()
+}
+
+fn unauthorized(res: XcosmResult) -> bool {
+  matches!(res, Err(XcosmError::Auth(AuthError::Unauthorized {})))
+}
+
+#[test]
+fn add_and_remove_many() {
+  let mut storage = MockStorage::new();
+  MEMBERS
+    .add_many(&mut storage, &addrs(&["a", "b", "c"]))
+    .unwrap();
+  MEMBERS.add(&mut storage, &Addr::unchecked("a")).unwrap();
+  MEMBERS.remove_many(&mut storage, &addrs(&["a", "c", "z"]));
+  assert!(!MEMBERS.contains(&storage, &Addr::unchecked("a")));
+  assert!(MEMBERS.contains(&storage, &Addr::unchecked("b")));
+  assert_eq!(
+    MEMBERS.list(&storage, None, None).unwrap().members,
+    addrs(&["b"])
+  );
+}
+
+#[test]
+fn authorize() {
+  let mut storage = MockStorage::new();
+  MEMBERS.add_many(&mut storage, &addrs(&["a", "b"])).unwrap();
+  let [a, b, z] = [
+    Addr::unchecked("a"),
+    Addr::unchecked("b"),
+    Addr::unchecked("z"),
+  ];
+  MEMBERS.authorize(&storage, &a).unwrap();
+  assert!(unauthorized(MEMBERS.authorize(&storage, &z)));
+
+  MEMBERS
+    .authorize_any(&storage, &[z.clone(), b.clone()])
+    .unwrap();
+  assert!(unauthorized(
+    MEMBERS.authorize_any(&storage, std::slice::from_ref(&z))
+  ));
+  assert!(unauthorized(MEMBERS.authorize_any(&storage, &[])));
+
+  MEMBERS
+    .authorize_all(&storage, &[a.clone(), b.clone()])
+    .unwrap();
+  assert!(unauthorized(MEMBERS.authorize_all(&storage, &[a, z])));
+  assert!(unauthorized(MEMBERS.authorize_all(&storage, &[])));
+}
+
+#[test]
+fn list_is_paginated_by_address() {
+  let mut storage = MockStorage::new();
+  MEMBERS
+    .add_many(&mut storage, &addrs(&["d", "b", "a", "c"]))
+    .unwrap();
+  let list = |start_after: Option<&str>, limit: Option<u32>| {
+    MEMBERS
+      .list(&storage, start_after.map(Addr::unchecked).as_ref(), limit)
+      .unwrap()
+      .members
+  };
+  assert_eq!(list(None, None), addrs(&["a", "b", "c", "d"]));
+  assert_eq!(list(None, Some(2)), addrs(&["a", "b"]));
+  assert_eq!(list(Some("b"), Some(2)), addrs(&["c", "d"]));
+  assert_eq!(list(Some("d"), None), addrs(&[]));
+}
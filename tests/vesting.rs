@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use cosmwasm_std::{testing::mock_env, BlockInfo, Decimal, Uint128};
+use xcosm::*;
+
+fn coins(amount: u128) -> CoinSet {
+  CoinSet::new(BTreeMap::from([("u".to_string(), Uint128::new(amount))]))
+}
+
+fn at_height(height: u64) -> BlockInfo {
+  BlockInfo {
+    height,
+    ..mock_env().block
+  }
+}
+
+#[test]
+fn revoke_clears_unvested() {
+  let curve = VestingCurve::Linear { start: 0, end: 100 };
+  let mut schedule = VestingSchedule::new(coins(1000), VestingClock::Height, curve).unwrap();
+  assert_eq!(schedule.unvested(&at_height(40)).unwrap(), coins(600));
+  assert_eq!(schedule.revoke(&at_height(40)).unwrap(), coins(600));
+  assert!(schedule.unvested(&at_height(40)).unwrap().is_empty());
+  assert!(schedule.unvested(&at_height(80)).unwrap().is_empty());
+  assert_eq!(schedule.vested(&at_height(80)).unwrap(), coins(400));
+  assert_eq!(schedule.release(&at_height(80)).unwrap(), coins(400));
+  assert!(schedule.revoke(&at_height(80)).is_err());
+}
+
+/// Amount of 1000u vested under `curve` at `moment`.
+fn vested(curve: &VestingCurve, moment: u64) -> u128 {
+  VestingSchedule::new(coins(1000), VestingClock::Height, curve.clone())
+    .unwrap()
+    .vested_at(moment)
+    .unwrap()
+    .amount_of("u")
+    .u128()
+}
+
+fn point(at: u64, percent: u64) -> VestingPoint {
+  VestingPoint {
+    at,
+    vested: Decimal::percent(percent),
+  }
+}
+
+fn invalid_schedule(curve: VestingCurve, expected: &str) -> bool {
+  matches!(
+    curve.validate(),
+    Err(XcosmError::Vesting(VestingError::InvalidSchedule { reason })) if reason == expected
+  )
+}
+
+#[test]
+fn cliff_linear_boundaries() {
+  let curve = VestingCurve::CliffLinear {
+    start: 0,
+    cliff: 40,
+    end: 100,
+  };
+  assert_eq!(vested(&curve, 0), 0);
+  assert_eq!(vested(&curve, 39), 0);
+  assert_eq!(vested(&curve, 40), 400);
+  assert_eq!(vested(&curve, 70), 700);
+  assert_eq!(vested(&curve, 100), 1000);
+  assert_eq!(vested(&curve, 200), 1000);
+}
+
+#[test]
+fn periodic_step_edges() {
+  let curve = VestingCurve::Periodic {
+    start: 10,
+    period: 10,
+    steps: 4,
+  };
+  assert_eq!(vested(&curve, 0), 0);
+  assert_eq!(vested(&curve, 19), 0);
+  assert_eq!(vested(&curve, 20), 250);
+  assert_eq!(vested(&curve, 29), 250);
+  assert_eq!(vested(&curve, 30), 500);
+  assert_eq!(vested(&curve, 50), 1000);
+  // capped at `steps`
+  assert_eq!(vested(&curve, 1000), 1000);
+}
+
+#[test]
+fn piecewise_boundaries() {
+  let curve = VestingCurve::Piecewise {
+    points: vec![point(10, 20), point(20, 50), point(40, 100)],
+  };
+  assert_eq!(vested(&curve, 9), 0);
+  assert_eq!(vested(&curve, 10), 200);
+  assert_eq!(vested(&curve, 15), 350);
+  assert_eq!(vested(&curve, 20), 500);
+  assert_eq!(vested(&curve, 30), 750);
+  assert_eq!(vested(&curve, 40), 1000);
+  assert_eq!(vested(&curve, 100), 1000);
+}
+
+#[test]
+fn piecewise_validation() {
+  assert!(invalid_schedule(
+    VestingCurve::Piecewise {
+      points: vec![point(20, 50), point(10, 100)],
+    },
+    "points must be ascending"
+  ));
+  assert!(invalid_schedule(
+    VestingCurve::Piecewise {
+      points: vec![point(10, 60), point(20, 50), point(30, 100)],
+    },
+    "points must be ascending"
+  ));
+  assert!(invalid_schedule(
+    VestingCurve::Piecewise {
+      points: vec![point(10, 50), point(20, 90)],
+    },
+    "points must end fully vested"
+  ));
+  assert!(invalid_schedule(
+    VestingCurve::Piecewise { points: vec![] },
+    "points must end fully vested"
+  ));
+  assert!(VestingSchedule::new(
+    coins(1000),
+    VestingClock::Height,
+    VestingCurve::Piecewise {
+      points: vec![point(10, 50)],
+    },
+  )
+  .is_err());
+}